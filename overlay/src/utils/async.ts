@@ -18,11 +18,19 @@ export async function executeInterval(
   action: VoidFunction,
   interval: number,
   maxIterations: number,
+  signal?: AbortSignal,
 ): Promise<void> {
   return new Promise((resolve) => {
     let iteration: number = 0;
 
     const intervalHandle: number = setInterval(() => {
+      // Stop early when aborted
+      if (signal !== undefined && signal.aborted) {
+        clearInterval(intervalHandle);
+        resolve();
+        return;
+      }
+
       action();
       iteration += 1;
 
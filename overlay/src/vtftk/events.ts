@@ -33,6 +33,9 @@ export type EventSourceData = {
   modelParameters: ModelParameters | undefined;
 };
 
+// Aborted when in progress outcomes are flushed, replaced after each flush
+let flushController = new AbortController();
+
 export function createEventSource(data: EventSourceData) {
   const eventSource = new EventSource(new URL("/events", BACKEND_HTTP));
 
@@ -111,6 +114,11 @@ async function onMessage(data: EventSourceData, event: any) {
       break;
     }
 
+    case "FlushOutcomes": {
+      onFlushOutcomesEvent();
+      break;
+    }
+
     case "UpdateHotkeys": {
       if (data.vtSocket) {
         onUpdateHotkeysEvent(data.vtSocket);
@@ -159,16 +167,28 @@ async function onUpdateHotkeysEvent(vtSocket: VTubeStudioWebSocket) {
   return hotkeys;
 }
 
+function onFlushOutcomesEvent() {
+  flushController.abort();
+  flushController = new AbortController();
+}
+
 async function onPlaySoundEvent(appData: AppData, config: Sound) {
+  const signal = flushController.signal;
   const audio = await loadAudio(config.src);
+  if (signal.aborted) return;
+
   audio.volume = config.volume * appData.sounds_config.global_volume;
+  signal.addEventListener("abort", () => audio.pause(), { once: true });
   audio.play();
 }
 
 async function onPlaySoundSeqEvent(appData: AppData, configs: Sound[]) {
+  const signal = flushController.signal;
   const sounds = await loadSounds(configs);
 
   for (const config of configs) {
+    if (signal.aborted) return;
+
     console.debug("Playing sound config", config);
 
     const soundData = sounds.get(config.id);
@@ -185,6 +205,15 @@ async function onPlaySoundSeqEvent(appData: AppData, configs: Sound[]) {
     const completePromise = new Promise<void>((resolve, reject) => {
       audio.onended = () => resolve();
       audio.onerror = () => reject();
+
+      signal.addEventListener(
+        "abort",
+        () => {
+          audio.pause();
+          resolve();
+        },
+        { once: true },
+      );
     });
 
     audio.play();
@@ -256,6 +285,7 @@ async function onThrowItemEvent(
   items: ItemWithSounds,
   config: ThrowItemConfig,
 ) {
+  const signal = flushController.signal;
   const [loadedItems, loadedSounds] = await Promise.all([
     loadItems(items.items),
     loadSounds(items.impact_sounds),
@@ -288,6 +318,7 @@ async function onThrowItemEvent(
       },
       config.frequency,
       config.amount,
+      signal,
     );
   }
 }
@@ -98,9 +98,9 @@ export function executeCommandOutlet(
       return;
     }
 
-    // Send the chat response if the return value is a string
+    // Reply to the command message if the return value is a string
     if (typeof value === "string") {
-      await api.twitch.sendChat(value);
+      await api.twitch.reply(value, baseContext.messageId);
     }
   });
 }
@@ -8,6 +8,19 @@ export interface TwitchEventUser {
 
 export type SubscriptionTier = "1000" | "2000" | "3000" | "Prime" | string;
 
+export type PollChoice = {
+  id: string;
+  title: string;
+  votes: number;
+};
+
+export type PredictionOutcome = {
+  id: string;
+  title: string;
+  users: number;
+  channel_points: number;
+};
+
 export type EventData = {
   user: TwitchEventUser;
 };
@@ -18,6 +31,7 @@ export type EventInputData = {
     reward_id: string;
     cost: number;
     user_input: string;
+    redemption_id: string | null;
   };
   cheerBits: {
     bits: number;
@@ -46,10 +60,35 @@ export type EventInputData = {
     message: string;
     fragments: unknown[];
     cheer: number | null;
+    captures: {
+      groups: (string | null)[];
+      named: Record<string, string>;
+    };
   };
   raid: {
     viewers: number;
   };
+  poll: {
+    title: string;
+    choices: PollChoice[];
+    winning_choice: PollChoice | null;
+    winning_choices: PollChoice[];
+  };
+  prediction: {
+    title: string;
+    outcomes: PredictionOutcome[];
+    winning_outcome: PredictionOutcome | null;
+    canceled: boolean;
+  };
+  hypeTrain: {
+    level: number;
+    total: number;
+    top_contributions: {
+      user: TwitchEventUser;
+      type: "bits" | "subscription" | "other" | string;
+      total: number;
+    }[];
+  };
 };
 
 export type EventInputValue = EventInputData[keyof EventInputData];
@@ -19,6 +19,24 @@ export function sendChat(message: string): Promise<void> {
   return Deno.core.ops.op_twitch_send_chat(message);
 }
 
+/**
+ * Send a chat message to twitch as a reply to another chat message,
+ * the reply is threaded under the original message
+ *
+ * Within commands `ctx.messageId` can be used to reply to the
+ * message that triggered the command
+ *
+ * @param message Message to send
+ * @param messageId ID of the chat message to reply to
+ * @returns Promise resolved when the message has sent
+ */
+export function reply(message: string, messageId: string): Promise<void> {
+  if (messageId === undefined) throw new Error("messageId must be provided");
+  if (typeof messageId !== "string") throw new Error("messageId is invalid");
+
+  return Deno.core.ops.op_twitch_send_chat_reply(message, messageId);
+}
+
 export type TwitchAnnouncementColor =
   | "blue"
   | "green"
@@ -164,6 +182,42 @@ export async function getFollower(
   };
 }
 
+/**
+ * Creates a new poll on the twitch channel
+ *
+ * @param title Title of the poll
+ * @param choices Choices to vote between (2-5 choices)
+ * @param duration Duration in seconds the poll should run for (15-1800 seconds)
+ * @returns Promise resolved with the ID of the created poll
+ */
+export function createPoll(
+  title: string,
+  choices: string[],
+  duration: number,
+): Promise<string> {
+  return Deno.core.ops.op_twitch_create_poll(title, choices, duration);
+}
+
+/**
+ * Creates a new prediction on the twitch channel
+ *
+ * @param title Title of the prediction
+ * @param outcomes Outcomes to predict between (2-10 outcomes)
+ * @param predictionWindow Duration in seconds predictions can be made for (30-1800 seconds)
+ * @returns Promise resolved with the ID of the created prediction
+ */
+export function createPrediction(
+  title: string,
+  outcomes: string[],
+  predictionWindow: number,
+): Promise<string> {
+  return Deno.core.ops.op_twitch_create_prediction(
+    title,
+    outcomes,
+    predictionWindow,
+  );
+}
+
 /**
  * Attempts to extract a username from the provided arg
  *
@@ -489,3 +489,81 @@ export function triggerVTHotkeyByName(
     ignoreCase,
   );
 }
+
+/**
+ * Get the remaining cooldown for an event
+ *
+ * @param eventId The ID of the event
+ * @param userId Optional ID of the user, used for per user cooldowns
+ * @returns Promise resolved with the remaining cooldown in milliseconds
+ * or null if the event is not on cooldown
+ */
+export function getEventCooldownRemaining(
+  eventId: string,
+  userId: string | null = null,
+): Promise<number | null> {
+  return Deno.core.ops.op_vtftk_get_event_cooldown_remaining(eventId, userId);
+}
+
+/**
+ * Get the remaining cooldown for a command
+ *
+ * @param commandId The ID of the command
+ * @param userId Optional ID of the user, used for per user cooldowns
+ * @returns Promise resolved with the remaining cooldown in milliseconds
+ * or null if the command is not on cooldown
+ */
+export function getCommandCooldownRemaining(
+  commandId: string,
+  userId: string | null = null,
+): Promise<number | null> {
+  return Deno.core.ops.op_vtftk_get_command_cooldown_remaining(
+    commandId,
+    userId,
+  );
+}
+
+/**
+ * Group of events and commands within VTFTK
+ *
+ * @member id Unique ID of the group
+ * @member name Name of the group
+ * @member enabled Whether the events and commands in the group are enabled
+ * @member default_cooldown Cooldown in milliseconds for members without their own cooldown
+ * @member default_require_role Required role for members that don't require a role
+ * @member order Order the group appears in the UI
+ * @member created_at When the group was created
+ */
+export interface GroupModel {
+  id: string;
+  name: string;
+  enabled: boolean;
+  default_cooldown: number | null;
+  default_require_role: string;
+  order: number;
+  created_at: string;
+}
+
+/**
+ * Get an event and command group by name, ignores case
+ *
+ * @param name The name of the group
+ * @returns Promise resolved with the group or null if no group has the name
+ */
+export function getGroupByName(name: string): Promise<GroupModel | null> {
+  return Deno.core.ops.op_vtftk_get_group_by_name(name);
+}
+
+/**
+ * Enable or disable all the events and commands in a group
+ *
+ * @param groupId The ID of the group
+ * @param enabled Whether the group should be enabled
+ * @returns Promise resolved when the group has been updated
+ */
+export function setGroupEnabled(
+  groupId: string,
+  enabled: boolean,
+): Promise<void> {
+  return Deno.core.ops.op_vtftk_set_group_enabled(groupId, enabled);
+}
@@ -4,11 +4,17 @@
 
 use crate::database::entity::{
     command_logs::CommandLogsModel,
-    commands::{CreateCommand, UpdateCommand},
+    commands::{CommandOutcome, CreateCommand, UpdateCommand},
     shared::{ExecutionsQuery, LogsQuery, UpdateOrdering},
     CommandExecutionModel, CommandModel,
 };
+use crate::events::{
+    cooldowns::CooldownTracker,
+    feedback::validate_feedback_replies,
+    template::{validate_template, TemplateVariables},
+};
 use anyhow::Context;
+use chrono::Utc;
 use sea_orm::{DatabaseConnection, ModelTrait};
 use tauri::State;
 use uuid::Uuid;
@@ -41,6 +47,9 @@ pub async fn create_command(
     db: State<'_, DatabaseConnection>,
 ) -> CmdResult<CommandModel> {
     let db = db.inner();
+    validate_command_outcome(&create.outcome)?;
+    validate_feedback_replies(&create.feedback_replies)?;
+
     let command = CommandModel::create(db, create).await?;
     Ok(command)
 }
@@ -56,6 +65,15 @@ pub async fn update_command(
     let command = CommandModel::get_by_id(db, command_id)
         .await?
         .context("command not found")?;
+
+    if let Some(outcome) = update.outcome.as_ref() {
+        validate_command_outcome(outcome)?;
+    }
+
+    if let Some(feedback_replies) = update.feedback_replies.as_ref() {
+        validate_feedback_replies(feedback_replies)?;
+    }
+
     let command = command.update(db, update).await?;
     Ok(command)
 }
@@ -129,10 +147,26 @@ pub async fn get_command_executions(
 pub async fn delete_command_executions(
     execution_ids: Vec<Uuid>,
     db: State<'_, DatabaseConnection>,
+    cooldowns: State<'_, CooldownTracker>,
 ) -> CmdResult<()> {
     let db = db.inner();
 
     CommandExecutionModel::delete_many(db, &execution_ids).await?;
 
+    // Deleted executions no longer count towards cooldowns
+    cooldowns.reload(db, Utc::now()).await?;
+
     Ok(())
 }
+
+/// Validates the command outcome, ensures the message template only
+/// uses known variables
+fn validate_command_outcome(outcome: &CommandOutcome) -> anyhow::Result<()> {
+    match outcome {
+        CommandOutcome::Template { message } => {
+            validate_template(message, TemplateVariables::Message)
+                .context("invalid command message template")
+        }
+        CommandOutcome::Script { .. } => Ok(()),
+    }
+}
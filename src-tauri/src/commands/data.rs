@@ -1,6 +1,6 @@
 use crate::{
     commands::CmdResult,
-    events::EventMessage,
+    events::{pause::set_outcomes_paused, queue::OutcomeQueue, EventMessage},
     state::{
         app_data::{AppData, AppDataStore},
         runtime_app_data::{RuntimeAppData, RuntimeAppDataStore},
@@ -49,12 +49,18 @@ pub async fn get_runtime_app_data(
 
 #[tauri::command]
 pub async fn set_app_data(
-    app_data: AppData,
+    mut app_data: AppData,
     app_data_store: tauri::State<'_, AppDataStore>,
     event_sender: tauri::State<'_, broadcast::Sender<EventMessage>>,
 ) -> CmdResult<bool> {
     app_data_store
-        .write(|old_app_data| *old_app_data = app_data.clone())
+        .write(|old_app_data| {
+            // Paused state is only changed through [set_paused]
+            app_data.paused = old_app_data.paused;
+            // Twitch accounts are only changed through the OAuth routes
+            app_data.twitch_config = old_app_data.twitch_config.clone();
+            *old_app_data = app_data.clone();
+        })
         .await
         .context("write app data")?;
 
@@ -65,6 +71,29 @@ pub async fn set_app_data(
     Ok(true)
 }
 
+/// Pause or resume all outcomes, optionally flushing any queued outcomes
+#[tauri::command]
+pub async fn set_paused(
+    paused: bool,
+    flush: bool,
+    app_data: tauri::State<'_, AppDataStore>,
+    runtime_app_data: tauri::State<'_, RuntimeAppDataStore>,
+    event_sender: tauri::State<'_, broadcast::Sender<EventMessage>>,
+    outcome_queue: tauri::State<'_, OutcomeQueue>,
+) -> CmdResult<()> {
+    set_outcomes_paused(
+        &app_data,
+        &runtime_app_data,
+        &event_sender,
+        &outcome_queue,
+        paused,
+        flush,
+    )
+    .await?;
+
+    Ok(())
+}
+
 #[derive(Debug, Deserialize)]
 pub enum FileType {
     ThrowableImage,
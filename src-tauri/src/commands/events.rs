@@ -4,22 +4,28 @@
 
 use std::sync::Arc;
 
-use crate::database::entity::events::{EventTrigger, EventTriggerType};
+use crate::database::entity::events::{EventCooldown, EventTrigger, EventTriggerType};
 use crate::database::entity::shared::{ExecutionsQuery, LogsQuery, UpdateOrdering};
 use crate::database::entity::{EventExecutionModel, EventLogsModel};
-use crate::events::outcome::produce_outcome_message;
+use crate::events::cooldowns::CooldownTracker;
+use crate::events::feedback::validate_feedback_replies;
+use crate::events::outcome::{produce_outcome_message, validate_event_outcome, OutcomeContext};
+use crate::events::processing::get_cooldown_reward_id;
 use crate::events::scheduler::SchedulerHandle;
 use crate::events::EventMessage;
 use crate::script::runtime::ScriptExecutorHandle;
-use crate::twitch::manager::TwitchManager;
+use crate::twitch::chat_queue::ChatPriority;
+use crate::twitch::manager::{CreateReward, TwitchManager};
 use crate::{
     database::entity::{
         events::{CreateEvent, UpdateEvent},
         EventModel,
     },
-    events::matching::EventData,
+    events::matching::{validate_event_conditions, validate_event_trigger, EventData},
 };
-use anyhow::Context;
+use anyhow::{anyhow, bail, Context};
+use chrono::{DateTime, Utc};
+use log::error;
 use sea_orm::{DatabaseConnection, ModelTrait};
 use tauri::State;
 use tokio::sync::broadcast;
@@ -52,8 +58,15 @@ pub async fn create_event(
     create: CreateEvent,
     db: State<'_, DatabaseConnection>,
     scheduler: State<'_, SchedulerHandle>,
+    twitch_manager: State<'_, Arc<TwitchManager>>,
 ) -> CmdResult<EventModel> {
     let db = db.inner();
+    validate_event_trigger(&create.trigger)?;
+    validate_event_conditions(&create.conditions)?;
+    validate_event_outcome(&create.outcome)?;
+    validate_feedback_replies(&create.feedback_replies)?;
+    validate_reward_cooldown_pause(&twitch_manager, &create.trigger, &create.cooldown).await?;
+
     let event = EventModel::create(db, create).await?;
 
     // Update the event scheduler
@@ -64,6 +77,59 @@ pub async fn create_event(
     Ok(event)
 }
 
+/// Create a new event along with a new channel point reward
+/// to trigger it, the event must use a redeem trigger which
+/// will be pointed at the new reward
+#[tauri::command]
+pub async fn create_event_with_reward(
+    mut create: CreateEvent,
+    reward: CreateReward,
+    db: State<'_, DatabaseConnection>,
+    twitch_manager: State<'_, Arc<TwitchManager>>,
+) -> CmdResult<EventModel> {
+    let db = db.inner();
+
+    let (auto_fulfill, auto_refund) = match &create.trigger {
+        EventTrigger::Redeem {
+            auto_fulfill,
+            auto_refund,
+            ..
+        } => (*auto_fulfill, *auto_refund),
+        _ => return Err(anyhow!("events created with a reward must use a redeem trigger").into()),
+    };
+
+    // Validate before creating the reward so a rejected event
+    // doesn't leave behind an unused reward
+    validate_event_trigger(&create.trigger)?;
+    validate_event_conditions(&create.conditions)?;
+    validate_event_outcome(&create.outcome)?;
+    validate_feedback_replies(&create.feedback_replies)?;
+
+    let reward = twitch_manager
+        .create_reward(reward)
+        .await
+        .context("failed to create reward")?;
+
+    // Point the trigger at the new reward
+    create.trigger = EventTrigger::Redeem {
+        reward_id: reward.id.to_string(),
+        auto_fulfill,
+        auto_refund,
+    };
+
+    match EventModel::create(db, create).await {
+        Ok(event) => Ok(event),
+        Err(err) => {
+            // Remove the reward that no longer has an event
+            if let Err(err) = twitch_manager.delete_reward(reward.id.as_str()).await {
+                error!("failed to delete reward for failed event: {err:?}");
+            }
+
+            Err(err.into())
+        }
+    }
+}
+
 /// Update an existing event
 #[tauri::command]
 pub async fn update_event(
@@ -71,11 +137,36 @@ pub async fn update_event(
     update: UpdateEvent,
     db: State<'_, DatabaseConnection>,
     scheduler: State<'_, SchedulerHandle>,
+    twitch_manager: State<'_, Arc<TwitchManager>>,
 ) -> CmdResult<EventModel> {
     let db = db.inner();
     let event = EventModel::get_by_id(db, event_id)
         .await?
         .context("event not found")?;
+
+    if let Some(trigger) = update.trigger.as_ref() {
+        validate_event_trigger(trigger)?;
+    }
+
+    if let Some(conditions) = update.conditions.as_ref() {
+        validate_event_conditions(conditions)?;
+    }
+
+    if let Some(outcome) = update.outcome.as_ref() {
+        validate_event_outcome(outcome)?;
+    }
+
+    if let Some(feedback_replies) = update.feedback_replies.as_ref() {
+        validate_feedback_replies(feedback_replies)?;
+    }
+
+    validate_reward_cooldown_pause(
+        &twitch_manager,
+        update.trigger.as_ref().unwrap_or(&event.trigger),
+        update.cooldown.as_ref().unwrap_or(&event.cooldown),
+    )
+    .await?;
+
     let event = event.update(db, update).await?;
 
     // Update the event scheduler
@@ -110,12 +201,48 @@ pub async fn delete_event(
     Ok(())
 }
 
+/// Ensures the reward for a redeem event with a global cooldown can be
+/// paused while the event is on cooldown, Twitch only allows rewards
+/// created by the app to be paused by it
+async fn validate_reward_cooldown_pause(
+    twitch_manager: &TwitchManager,
+    trigger: &EventTrigger,
+    cooldown: &EventCooldown,
+) -> anyhow::Result<()> {
+    let reward_id = match get_cooldown_reward_id(trigger, cooldown) {
+        Some(value) => value,
+        None => return Ok(()),
+    };
+
+    let manageable = twitch_manager
+        .is_reward_manageable(reward_id)
+        .await
+        .context("failed to check reward")?;
+
+    if !manageable {
+        bail!(
+            "global cooldowns pause the reward, which is only possible for rewards created by the app"
+        );
+    }
+
+    Ok(())
+}
+
 async fn update_scheduler_events(db: &DatabaseConnection, scheduler: &SchedulerHandle) {
     if let Ok(events) = EventModel::get_by_trigger_type(db, EventTriggerType::Timer).await {
         _ = scheduler.update_events(events).await;
     }
 }
 
+/// Get the next time a timer event is scheduled to run
+#[tauri::command]
+pub async fn get_event_next_run(
+    event_id: Uuid,
+    scheduler: State<'_, SchedulerHandle>,
+) -> CmdResult<Option<DateTime<Utc>>> {
+    Ok(scheduler.get_next_run(event_id))
+}
+
 /// Get a specific event by ID
 #[tauri::command]
 pub async fn test_event_by_id(
@@ -132,11 +259,15 @@ pub async fn test_event_by_id(
         .await?
         .context("unknown event")?;
 
-    if let Some(msg) =
-        produce_outcome_message(db, &twitch_manager, &script_handle, event, event_data).await?
-    {
-        _ = event_sender.send(msg);
-    }
+    let ctx = OutcomeContext {
+        db,
+        twitch_manager: &twitch_manager,
+        script_handle: &script_handle,
+        event_sender: &event_sender,
+        chat_priority: ChatPriority::Normal,
+    };
+
+    produce_outcome_message(ctx, event.id, event.outcome, event_data).await?;
 
     Ok(())
 }
@@ -171,11 +302,15 @@ pub async fn get_event_executions(
 pub async fn delete_event_executions(
     execution_ids: Vec<Uuid>,
     db: State<'_, DatabaseConnection>,
+    cooldowns: State<'_, CooldownTracker>,
 ) -> CmdResult<()> {
     let db = db.inner();
 
     EventExecutionModel::delete_many(db, &execution_ids).await?;
 
+    // Deleted executions no longer count towards cooldowns
+    cooldowns.reload(db, Utc::now()).await?;
+
     Ok(())
 }
 
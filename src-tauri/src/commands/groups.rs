@@ -0,0 +1,120 @@
+//! # Groups
+//!
+//! Commands for interacting with event and command groups from the frontend
+
+use crate::{
+    database::entity::{
+        groups::{CreateGroup, UpdateGroup},
+        shared::UpdateOrdering,
+        GroupModel,
+    },
+    events::group_cache::GroupCache,
+};
+use anyhow::Context;
+use sea_orm::DatabaseConnection;
+use tauri::State;
+use uuid::Uuid;
+
+use super::CmdResult;
+
+/// Get all groups
+#[tauri::command]
+pub async fn get_groups(db: State<'_, DatabaseConnection>) -> CmdResult<Vec<GroupModel>> {
+    let db = db.inner();
+    let groups = GroupModel::all(db).await?;
+    Ok(groups)
+}
+
+/// Get a specific group by ID
+#[tauri::command]
+pub async fn get_group_by_id(
+    group_id: Uuid,
+    db: State<'_, DatabaseConnection>,
+) -> CmdResult<Option<GroupModel>> {
+    let db = db.inner();
+    let group = GroupModel::get_by_id(db, group_id).await?;
+    Ok(group)
+}
+
+/// Create a new group
+#[tauri::command]
+pub async fn create_group(
+    create: CreateGroup,
+    db: State<'_, DatabaseConnection>,
+    groups: State<'_, GroupCache>,
+) -> CmdResult<GroupModel> {
+    let db = db.inner();
+    let group = GroupModel::create(db, create).await?;
+    groups.invalidate().await;
+    Ok(group)
+}
+
+/// Update an existing group
+#[tauri::command]
+pub async fn update_group(
+    group_id: Uuid,
+    update: UpdateGroup,
+    db: State<'_, DatabaseConnection>,
+    groups: State<'_, GroupCache>,
+) -> CmdResult<GroupModel> {
+    let db = db.inner();
+    let group = GroupModel::get_by_id(db, group_id)
+        .await?
+        .context("group not found")?;
+    let group = group.update(db, update).await?;
+    groups.invalidate().await;
+    Ok(group)
+}
+
+/// Enable or disable all the events and commands in a group
+#[tauri::command]
+pub async fn set_group_enabled(
+    group_id: Uuid,
+    enabled: bool,
+    db: State<'_, DatabaseConnection>,
+    groups: State<'_, GroupCache>,
+) -> CmdResult<GroupModel> {
+    let db = db.inner();
+    let group = GroupModel::get_by_id(db, group_id)
+        .await?
+        .context("group not found")?;
+    let group = group
+        .update(
+            db,
+            UpdateGroup {
+                enabled: Some(enabled),
+                ..Default::default()
+            },
+        )
+        .await?;
+    groups.invalidate().await;
+    Ok(group)
+}
+
+/// Delete a group, events and commands in the group are kept
+/// but no longer belong to a group
+#[tauri::command]
+pub async fn delete_group(
+    group_id: Uuid,
+    db: State<'_, DatabaseConnection>,
+    groups: State<'_, GroupCache>,
+) -> CmdResult<()> {
+    let db = db.inner();
+    let group = GroupModel::get_by_id(db, group_id)
+        .await?
+        .context("group not found")?;
+    group.delete_group(db).await?;
+    groups.invalidate().await;
+    Ok(())
+}
+
+/// Update the ordering of groups
+#[tauri::command]
+pub async fn update_group_orderings(
+    update: Vec<UpdateOrdering>,
+    db: State<'_, DatabaseConnection>,
+) -> CmdResult<()> {
+    let db = db.inner();
+    GroupModel::update_order(db, update).await?;
+    Ok(())
+}
@@ -5,6 +5,7 @@ pub mod calibration;
 pub mod commands;
 pub mod data;
 pub mod events;
+pub mod groups;
 pub mod items;
 pub mod sounds;
 pub mod test;
@@ -1,6 +1,9 @@
-use crate::constants::{TWITCH_CLIENT_ID, TWITCH_REQUIRED_SCOPES};
+use crate::constants::{TWITCH_BOT_REQUIRED_SCOPES, TWITCH_CLIENT_ID, TWITCH_REQUIRED_SCOPES};
 use crate::state::app_data::AppDataStore;
-use crate::{commands::CmdResult, twitch::manager::TwitchManager};
+use crate::{
+    commands::CmdResult,
+    twitch::manager::{CreateReward, TwitchManager, TwitchUser, UpdateReward},
+};
 use anyhow::Context;
 use log::debug;
 use reqwest::Url;
@@ -33,6 +36,90 @@ pub async fn refresh_redeems_list(
     Ok(true)
 }
 
+/// Create a new channel point reward
+#[tauri::command]
+pub async fn create_reward(
+    create: CreateReward,
+    twitch_manager: State<'_, Arc<TwitchManager>>,
+) -> CmdResult<CustomReward> {
+    Ok(twitch_manager
+        .create_reward(create)
+        .await
+        .context("failed to create reward")?)
+}
+
+/// Update an existing channel point reward
+#[tauri::command]
+pub async fn update_reward(
+    reward_id: String,
+    update: UpdateReward,
+    twitch_manager: State<'_, Arc<TwitchManager>>,
+) -> CmdResult<CustomReward> {
+    Ok(twitch_manager
+        .update_reward(&reward_id, update)
+        .await
+        .context("failed to update reward")?)
+}
+
+/// Pause or unpause a channel point reward
+#[tauri::command]
+pub async fn set_reward_paused(
+    reward_id: String,
+    paused: bool,
+    twitch_manager: State<'_, Arc<TwitchManager>>,
+) -> CmdResult<CustomReward> {
+    Ok(twitch_manager
+        .set_reward_paused(&reward_id, paused)
+        .await
+        .context("failed to update reward")?)
+}
+
+/// Enable or disable a channel point reward
+#[tauri::command]
+pub async fn set_reward_enabled(
+    reward_id: String,
+    enabled: bool,
+    twitch_manager: State<'_, Arc<TwitchManager>>,
+) -> CmdResult<CustomReward> {
+    let update = UpdateReward {
+        is_enabled: Some(enabled),
+        ..Default::default()
+    };
+
+    Ok(twitch_manager
+        .update_reward(&reward_id, update)
+        .await
+        .context("failed to update reward")?)
+}
+
+/// Delete a channel point reward
+#[tauri::command]
+pub async fn delete_reward(
+    reward_id: String,
+    twitch_manager: State<'_, Arc<TwitchManager>>,
+) -> CmdResult<()> {
+    twitch_manager
+        .delete_reward(&reward_id)
+        .await
+        .context("failed to delete reward")?;
+    Ok(())
+}
+
+/// Find a twitch user by their username
+///
+/// Used on the frontend to resolve the user ID when adding
+/// users to the allow and deny lists
+#[tauri::command]
+pub async fn get_twitch_user_by_username(
+    username: String,
+    twitch_manager: State<'_, Arc<TwitchManager>>,
+) -> CmdResult<Option<TwitchUser>> {
+    Ok(twitch_manager
+        .get_user_by_username(&username)
+        .await
+        .context("failed to find user")?)
+}
+
 /// Obtain a URL for use logging into twitch using OAuth2
 #[tauri::command]
 pub async fn get_twitch_oauth_uri(state: tauri::State<'_, AppDataStore>) -> CmdResult<String> {
@@ -52,6 +139,66 @@ pub async fn get_twitch_oauth_uri(state: tauri::State<'_, AppDataStore>) -> CmdR
     Ok(url.to_string())
 }
 
+/// Obtain a URL for use logging into the bot account using OAuth2
+///
+/// Forces the twitch login prompt so a different account to the
+/// broadcaster can be chosen
+#[tauri::command]
+pub async fn get_twitch_bot_oauth_uri(state: tauri::State<'_, AppDataStore>) -> CmdResult<String> {
+    let http_port = {
+        let app_data = state.read().await;
+        app_data.main_config.get_http_port()
+    };
+
+    let redirect_url = format!("http://localhost:{http_port}/oauth/bot",);
+    let redirect_url = Url::parse(&redirect_url).context("invalid redirect_uri")?;
+
+    let (url, _csrf) =
+        ImplicitUserTokenBuilder::new(ClientId::from_static(TWITCH_CLIENT_ID), redirect_url)
+            .set_scopes(TWITCH_BOT_REQUIRED_SCOPES.to_vec())
+            .force_verify(true)
+            .generate_url();
+
+    Ok(url.to_string())
+}
+
+/// Get the bot account chat messages are sent from, [None] when
+/// no bot account is connected
+#[tauri::command]
+pub async fn get_twitch_bot_user(
+    twitch_manager: State<'_, Arc<TwitchManager>>,
+) -> CmdResult<Option<TwitchUser>> {
+    let token = match twitch_manager.get_bot_token().await {
+        Some(value) => value,
+        None => return Ok(None),
+    };
+
+    Ok(twitch_manager
+        .get_user_by_username(token.login.as_str())
+        .await
+        .context("failed to find bot user")?)
+}
+
+/// Logout from the bot account, chat messages will be sent
+/// from the broadcaster account
+#[tauri::command]
+pub async fn logout_bot(
+    app_data: State<'_, AppDataStore>,
+    twitch_manager: State<'_, Arc<TwitchManager>>,
+) -> CmdResult<()> {
+    twitch_manager.reset_bot().await;
+
+    app_data
+        .write(|app_data| {
+            app_data.twitch_config.bot_access_token = None;
+            app_data.twitch_config.bot_scopes = None;
+        })
+        .await
+        .context("saving app data")?;
+
+    Ok(())
+}
+
 #[tauri::command]
 pub async fn is_authenticated(state: tauri::State<'_, Arc<TwitchManager>>) -> Result<bool, ()> {
     Ok(state.is_authenticated().await)
@@ -4,12 +4,20 @@ use twitch_api::helix::Scope;
 /// twitch developer application client ID at https://dev.twitch.tv/console/apps
 pub const TWITCH_CLIENT_ID: &str = "x0zzeitiwvgblu743qnxzaipa9e01z";
 
+/// Chat command moderators can use to pause all outcomes
+pub const PAUSE_CHAT_COMMAND: &str = "!vtftk-pause";
+
+/// Chat command moderators can use to resume outcomes after pausing
+pub const RESUME_CHAT_COMMAND: &str = "!vtftk-resume";
+
 /// Scopes required from twitch by the app
 pub const TWITCH_REQUIRED_SCOPES: &[Scope] = &[
     // View live Stream Chat and Rooms messages
     Scope::UserReadChat,
     // View Channel Points rewards and their redemptions on your channel.
     Scope::ChannelReadRedemptions,
+    // Allow fulfilling and refunding channel point redemptions
+    Scope::ChannelManageRedemptions,
     // Get a list of all subscribers to your channel and check if a user is subscribed to your channel
     Scope::ChannelReadSubscriptions,
     // View your channel's Bits information
@@ -35,4 +43,18 @@ pub const TWITCH_REQUIRED_SCOPES: &[Scope] = &[
     Scope::ChannelManageBroadcast,
     // Scope to read ad break messages
     Scope::ChannelReadAds,
+    // Scope to read hype train events
+    Scope::ChannelReadHypeTrain,
+    // Allow reading poll events and creating polls
+    Scope::ChannelManagePolls,
+    // Allow reading prediction events and creating predictions
+    Scope::ChannelManagePredictions,
+];
+
+/// Scopes required from twitch for the optional bot account
+pub const TWITCH_BOT_REQUIRED_SCOPES: &[Scope] = &[
+    // Send chat messages
+    Scope::UserWriteChat,
+    // Allow sending chat announcements (Bot must be a moderator)
+    Scope::ModeratorManageAnnouncements,
 ];
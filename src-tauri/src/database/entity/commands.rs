@@ -1,7 +1,10 @@
 use super::{
     command_executions::{CommandExecutionColumn, CommandExecutionModel},
     command_logs::{CommandLogsColumn, CommandLogsModel},
-    shared::{DbResult, ExecutionsQuery, LogsQuery, MinimumRequireRole, UpdateOrdering},
+    shared::{
+        DbResult, ExecutionsQuery, FeedbackReplies, LogsQuery, MinimumRequireRole, UpdateOrdering,
+        UserAccessList,
+    },
 };
 use anyhow::Context;
 use chrono::Utc;
@@ -38,6 +41,12 @@ pub struct Model {
     pub cooldown: CommandCooldown,
     /// Minimum required role to trigger the command
     pub require_role: MinimumRequireRole,
+    /// Users specifically allowed or denied from triggering the command
+    pub user_access: UserAccessList,
+    /// Chat replies for when the command is skipped
+    pub feedback_replies: FeedbackReplies,
+    /// Group the command belongs to
+    pub group_id: Option<Uuid>,
     /// Ordering
     pub order: u32,
     // Date time of creation
@@ -106,6 +115,12 @@ pub struct CreateCommand {
     pub outcome: CommandOutcome,
     pub cooldown: CommandCooldown,
     pub require_role: MinimumRequireRole,
+    #[serde(default)]
+    pub user_access: UserAccessList,
+    #[serde(default)]
+    pub feedback_replies: FeedbackReplies,
+    #[serde(default)]
+    pub group_id: Option<Uuid>,
 }
 
 #[derive(Default, Deserialize)]
@@ -117,6 +132,10 @@ pub struct UpdateCommand {
     pub outcome: Option<CommandOutcome>,
     pub cooldown: Option<CommandCooldown>,
     pub require_role: Option<MinimumRequireRole>,
+    pub user_access: Option<UserAccessList>,
+    pub feedback_replies: Option<FeedbackReplies>,
+    #[serde(default, deserialize_with = "super::shared::deserialize_some")]
+    pub group_id: Option<Option<Uuid>>,
     pub order: Option<u32>,
 }
 
@@ -136,6 +155,9 @@ impl Model {
             outcome: Set(create.outcome),
             cooldown: Set(create.cooldown),
             require_role: Set(create.require_role),
+            user_access: Set(create.user_access),
+            feedback_replies: Set(create.feedback_replies),
+            group_id: Set(create.group_id),
             order: Set(0),
             created_at: Set(Utc::now()),
         };
@@ -151,35 +173,19 @@ impl Model {
         Ok(model)
     }
 
-    /// Find commands by the actual command trigger word
-    /// and only commands that are enabled
+    /// Find commands by the actual command trigger word, includes
+    /// disabled commands so they can reply when used
     pub async fn get_by_command<C>(db: &C, command: &str) -> DbResult<Vec<Model>>
     where
         C: ConnectionTrait + Send + 'static,
     {
         // TODO: Join against future aliases table
         Entity::find()
-            .filter(Column::Command.eq(command).and(Column::Enabled.eq(true)))
+            .filter(Column::Command.eq(command))
             .all(db)
             .await
     }
 
-    /// Find the most recent execution of this command
-    pub async fn last_execution<C>(
-        &self,
-        db: &C,
-        offset: u64,
-    ) -> DbResult<Option<CommandExecutionModel>>
-    where
-        C: ConnectionTrait + Send + 'static,
-    {
-        self.find_related(super::command_executions::Entity)
-            .order_by_desc(CommandExecutionColumn::CreatedAt)
-            .offset(offset)
-            .one(db)
-            .await
-    }
-
     /// Find a specific sound by ID
     pub async fn get_by_id<C>(db: &C, id: Uuid) -> DbResult<Option<Self>>
     where
@@ -214,6 +220,12 @@ impl Model {
         this.outcome = data.outcome.map(Set).unwrap_or(this.outcome);
         this.cooldown = data.cooldown.map(Set).unwrap_or(this.cooldown);
         this.require_role = data.require_role.map(Set).unwrap_or(this.require_role);
+        this.user_access = data.user_access.map(Set).unwrap_or(this.user_access);
+        this.feedback_replies = data
+            .feedback_replies
+            .map(Set)
+            .unwrap_or(this.feedback_replies);
+        this.group_id = data.group_id.map(Set).unwrap_or(this.group_id);
         this.order = data.order.map(Set).unwrap_or(this.order);
 
         let this = this.update(db).await?;
@@ -1,5 +1,5 @@
 use anyhow::Context;
-use sea_orm::{entity::prelude::*, ActiveValue::Set, FromJsonQueryResult};
+use sea_orm::{entity::prelude::*, ActiveValue::Set, FromJsonQueryResult, QueryOrder};
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 
@@ -103,6 +103,19 @@ impl Model {
         Entity::find().all(db).await
     }
 
+    /// Find all executions created after the provided date, ordered
+    /// from oldest to newest
+    pub async fn get_since<C>(db: &C, start_date: DateTimeUtc) -> DbResult<Vec<Self>>
+    where
+        C: ConnectionTrait + Send + 'static,
+    {
+        Entity::find()
+            .filter(Column::CreatedAt.gt(start_date))
+            .order_by_asc(Column::CreatedAt)
+            .all(db)
+            .await
+    }
+
     pub async fn delete_before<C>(db: &C, start_date: DateTimeUtc) -> DbResult<()>
     where
         C: ConnectionTrait + Send + 'static,
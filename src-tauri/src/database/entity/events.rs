@@ -1,16 +1,20 @@
 use super::{
     event_executions::{EventExecutionColumn, EventExecutionModel},
-    shared::{DbResult, ExecutionsQuery, LogsQuery, MinMax, MinimumRequireRole, UpdateOrdering},
+    shared::{
+        DbResult, ExecutionsQuery, FeedbackReplies, LogsQuery, MinMax, MinimumRequireRole,
+        UpdateOrdering, UserAccessList,
+    },
     EventLogsColumn, EventLogsModel,
 };
 use anyhow::Context;
-use chrono::Utc;
+use chrono::{NaiveTime, Utc, Weekday};
 use futures::{future::BoxFuture, stream::FuturesUnordered, TryStreamExt};
 use sea_orm::{
     entity::prelude::*, ActiveValue::Set, FromJsonQueryResult, IntoActiveModel, QueryOrder,
     QuerySelect, UpdateResult,
 };
 use serde::{Deserialize, Serialize};
+use twitch_api::types::SubscriptionTier;
 
 // Type alias helpers for the database entity types
 pub type EventModel = Model;
@@ -42,6 +46,14 @@ pub struct Model {
     pub require_role: MinimumRequireRole,
     /// Delay before executing the outcome
     pub outcome_delay: u32,
+    /// Conditions the event input must meet to trigger the event
+    pub conditions: EventConditions,
+    /// Users specifically allowed or denied from triggering the event
+    pub user_access: UserAccessList,
+    /// Chat replies for when the event is skipped
+    pub feedback_replies: FeedbackReplies,
+    /// Group the event belongs to
+    pub group_id: Option<Uuid>,
     /// Ordering
     pub order: u32,
 
@@ -67,6 +79,46 @@ impl Default for EventCooldown {
     }
 }
 
+/// List of conditions that must all be met for an event to run
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, FromJsonQueryResult)]
+#[serde(transparent)]
+pub struct EventConditions(pub Vec<EventCondition>);
+
+/// Condition checked against the event input data before
+/// the event is executed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum EventCondition {
+    /// Input amount (bits, raiders, gifted subs, months) must be within the range
+    InputAmount {
+        /// Minimum amount (inclusive)
+        min: Option<i64>,
+        /// Maximum amount (inclusive)
+        max: Option<i64>,
+    },
+    /// Subscription tier must match
+    SubscriptionTier {
+        /// Required subscription tier
+        tier: SubscriptionTier,
+    },
+    /// Input text (chat message, redeem input, bits message) must contain the value
+    InputTextContains {
+        /// Value to search for
+        value: String,
+        /// Whether to ignore case when searching
+        #[serde(default)]
+        ignore_case: bool,
+    },
+    /// Input text (chat message, redeem input, bits message) must match the pattern
+    InputTextMatches {
+        /// Regular expression pattern to match
+        pattern: String,
+        /// Whether to ignore case when matching
+        #[serde(default)]
+        ignore_case: bool,
+    },
+}
+
 /// Copy of the [EventTrigger] enum but string variants to
 /// support storing in the database as strings for querying
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, EnumIter, DeriveActiveEnum)]
@@ -92,6 +144,34 @@ pub enum EventTriggerType {
     AdBreakBegin,
     #[sea_orm(string_value = "ShoutoutReceive")]
     ShoutoutReceive,
+    #[sea_orm(string_value = "ChatMessage")]
+    ChatMessage,
+    #[sea_orm(string_value = "FirstMessage")]
+    FirstMessage,
+    #[sea_orm(string_value = "FirstStreamMessage")]
+    FirstStreamMessage,
+    #[sea_orm(string_value = "HypeTrainBegin")]
+    HypeTrainBegin,
+    #[sea_orm(string_value = "HypeTrainProgress")]
+    HypeTrainProgress,
+    #[sea_orm(string_value = "HypeTrainLevelUp")]
+    HypeTrainLevelUp,
+    #[sea_orm(string_value = "HypeTrainEnd")]
+    HypeTrainEnd,
+    #[sea_orm(string_value = "PollBegin")]
+    PollBegin,
+    #[sea_orm(string_value = "PollEnd")]
+    PollEnd,
+    #[sea_orm(string_value = "PredictionBegin")]
+    PredictionBegin,
+    #[sea_orm(string_value = "PredictionLock")]
+    PredictionLock,
+    #[sea_orm(string_value = "PredictionEnd")]
+    PredictionEnd,
+    #[sea_orm(string_value = "StreamOnline")]
+    StreamOnline,
+    #[sea_orm(string_value = "StreamOffline")]
+    StreamOffline,
 }
 
 impl EventTriggerType {
@@ -107,6 +187,20 @@ impl EventTriggerType {
             EventTrigger::Timer { .. } => EventTriggerType::Timer,
             EventTrigger::AdBreakBegin => EventTriggerType::AdBreakBegin,
             EventTrigger::ShoutoutReceive { .. } => EventTriggerType::ShoutoutReceive,
+            EventTrigger::ChatMessage { .. } => EventTriggerType::ChatMessage,
+            EventTrigger::FirstMessage => EventTriggerType::FirstMessage,
+            EventTrigger::FirstStreamMessage => EventTriggerType::FirstStreamMessage,
+            EventTrigger::HypeTrainBegin => EventTriggerType::HypeTrainBegin,
+            EventTrigger::HypeTrainProgress => EventTriggerType::HypeTrainProgress,
+            EventTrigger::HypeTrainLevelUp { .. } => EventTriggerType::HypeTrainLevelUp,
+            EventTrigger::HypeTrainEnd => EventTriggerType::HypeTrainEnd,
+            EventTrigger::PollBegin => EventTriggerType::PollBegin,
+            EventTrigger::PollEnd => EventTriggerType::PollEnd,
+            EventTrigger::PredictionBegin => EventTriggerType::PredictionBegin,
+            EventTrigger::PredictionLock => EventTriggerType::PredictionLock,
+            EventTrigger::PredictionEnd => EventTriggerType::PredictionEnd,
+            EventTrigger::StreamOnline => EventTriggerType::StreamOnline,
+            EventTrigger::StreamOffline => EventTriggerType::StreamOffline,
         }
     }
 }
@@ -118,6 +212,12 @@ pub enum EventTrigger {
     Redeem {
         /// ID of the reward required
         reward_id: String,
+        /// Mark the redemption as fulfilled when the outcome succeeds
+        #[serde(default)]
+        auto_fulfill: bool,
+        /// Refund the redemption when the outcome fails or is on cooldown
+        #[serde(default)]
+        auto_refund: bool,
     },
     /// Command was sent
     Command {
@@ -145,6 +245,21 @@ pub enum EventTrigger {
     Timer {
         /// Interval in seconds to run
         interval: u64,
+        /// Only run the timer while the stream is live
+        #[serde(default)]
+        only_while_live: bool,
+        /// Optional window restricting when the timer is allowed to run
+        #[serde(default)]
+        window: Option<TimerWindow>,
+        /// Maximum random delay in seconds added to each run
+        #[serde(default)]
+        jitter: u64,
+        /// Minimum number of chat messages since the last run required to run
+        #[serde(default)]
+        min_chat_messages: u64,
+        /// Minimum number of unique chatters since the last run required to run
+        #[serde(default)]
+        min_unique_chatters: u64,
     },
 
     /// Ad break started
@@ -155,6 +270,88 @@ pub enum EventTrigger {
         /// Minimum viewers required
         min_viewers: u32,
     },
+
+    /// Chat message contained a keyword or matched a pattern
+    ChatMessage {
+        /// Matcher to test the chat message against
+        matcher: ChatMessageMatcher,
+        /// Whether the matching should be case sensitive
+        #[serde(default)]
+        case_sensitive: bool,
+    },
+
+    /// Viewer sent their first message ever in the channel, uses the first
+    /// message marker from Twitch when present otherwise the first message
+    /// seen by the app once it has seen viewers from a previous stream
+    FirstMessage,
+
+    /// Viewer sent their first message during the current stream
+    FirstStreamMessage,
+
+    /// Hype train started
+    HypeTrainBegin,
+
+    /// Hype train progressed
+    HypeTrainProgress,
+
+    /// Hype train reached a new level
+    HypeTrainLevelUp {
+        /// Minimum level reached required to trigger
+        #[serde(default)]
+        min_level: u32,
+    },
+
+    /// Hype train ended
+    HypeTrainEnd,
+
+    /// Poll started
+    PollBegin,
+
+    /// Poll ended
+    PollEnd,
+
+    /// Prediction started
+    PredictionBegin,
+
+    /// Prediction locked, no more predictions can be made
+    PredictionLock,
+
+    /// Prediction ended
+    PredictionEnd,
+
+    /// Stream went online
+    StreamOnline,
+
+    /// Stream went offline
+    StreamOffline,
+}
+
+/// Window of time a timer event is allowed to run within
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimerWindow {
+    /// Local time the window opens
+    pub start: NaiveTime,
+    /// Local time the window closes, windows that close before they
+    /// open extend past midnight
+    pub end: NaiveTime,
+    /// Days of the week the window is open on, empty for every day
+    #[serde(default)]
+    pub days: Vec<Weekday>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum ChatMessageMatcher {
+    /// Message contains the keyword anywhere within it
+    Keyword {
+        /// Keyword to search for
+        keyword: String,
+    },
+    /// Message matches the regular expression
+    Regex {
+        /// Regular expression pattern
+        pattern: String,
+    },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -258,6 +455,36 @@ pub struct EventOutcomeChannelEmotes {
     pub amount: ThrowableAmountData,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EventOutcomeSequence {
+    /// Steps to run, in order
+    pub steps: Vec<EventOutcomeSequenceStep>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EventOutcomeSequenceStep {
+    /// Delay in milliseconds before running this step
+    #[serde(default)]
+    pub delay: u32,
+    /// Outcome to run for this step
+    pub outcome: EventOutcome,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EventOutcomeRandom {
+    /// Outcomes to choose from
+    pub choices: Vec<EventOutcomeRandomChoice>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EventOutcomeRandomChoice {
+    /// Weight of the choice, chance of being chosen is the weight
+    /// relative to the total weight of all the choices
+    pub weight: u32,
+    /// Outcome to run when chosen
+    pub outcome: EventOutcome,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, FromJsonQueryResult)]
 #[serde(tag = "type")]
 pub enum EventOutcome {
@@ -275,6 +502,10 @@ pub enum EventOutcome {
     Script(EventOutcomeScript),
     /// Throw the emotes of a specific channel
     ChannelEmotes(EventOutcomeChannelEmotes),
+    /// Run multiple outcomes one after the other
+    Sequence(EventOutcomeSequence),
+    /// Run one outcome chosen at random by weight
+    Random(EventOutcomeRandom),
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -309,6 +540,14 @@ pub struct CreateEvent {
     pub cooldown: EventCooldown,
     pub require_role: MinimumRequireRole,
     pub outcome_delay: u32,
+    #[serde(default)]
+    pub conditions: EventConditions,
+    #[serde(default)]
+    pub user_access: UserAccessList,
+    #[serde(default)]
+    pub feedback_replies: FeedbackReplies,
+    #[serde(default)]
+    pub group_id: Option<Uuid>,
 }
 
 #[derive(Default, Deserialize)]
@@ -320,6 +559,11 @@ pub struct UpdateEvent {
     pub cooldown: Option<EventCooldown>,
     pub require_role: Option<MinimumRequireRole>,
     pub outcome_delay: Option<u32>,
+    pub conditions: Option<EventConditions>,
+    pub user_access: Option<UserAccessList>,
+    pub feedback_replies: Option<FeedbackReplies>,
+    #[serde(default, deserialize_with = "super::shared::deserialize_some")]
+    pub group_id: Option<Option<Uuid>>,
     pub order: Option<u32>,
 }
 
@@ -340,6 +584,10 @@ impl Model {
             cooldown: Set(create.cooldown),
             require_role: Set(create.require_role),
             outcome_delay: Set(create.outcome_delay),
+            conditions: Set(create.conditions),
+            user_access: Set(create.user_access),
+            feedback_replies: Set(create.feedback_replies),
+            group_id: Set(create.group_id),
             order: Set(0),
             created_at: Set(Utc::now()),
         };
@@ -355,28 +603,27 @@ impl Model {
         Ok(model)
     }
 
-    /// Find the most recent execution of this event
-    pub async fn last_execution<C>(
-        &self,
-        db: &C,
-        offset: u64,
-    ) -> DbResult<Option<EventExecutionModel>>
+    /// Find a specific event by ID
+    pub async fn get_by_id<C>(db: &C, id: Uuid) -> DbResult<Option<Self>>
     where
         C: ConnectionTrait + Send + 'static,
     {
-        self.find_related(super::event_executions::Entity)
-            .order_by_desc(EventExecutionColumn::CreatedAt)
-            .offset(offset)
-            .one(db)
-            .await
+        Entity::find_by_id(id).one(db).await
     }
 
-    /// Find a specific event by ID
-    pub async fn get_by_id<C>(db: &C, id: Uuid) -> DbResult<Option<Self>>
+    /// Find events by trigger type including disabled events, used for
+    /// events triggered by users so disabled events can reply when used
+    pub async fn get_by_trigger_type_with_disabled<C>(
+        db: &C,
+        trigger_type: EventTriggerType,
+    ) -> DbResult<Vec<Self>>
     where
         C: ConnectionTrait + Send + 'static,
     {
-        Entity::find_by_id(id).one(db).await
+        Entity::find()
+            .filter(Column::TriggerType.eq(trigger_type))
+            .all(db)
+            .await
     }
 
     /// Find a specific event by a specific trigger type
@@ -431,6 +678,13 @@ impl Model {
         this.cooldown = data.cooldown.map(Set).unwrap_or(this.cooldown);
         this.require_role = data.require_role.map(Set).unwrap_or(this.require_role);
         this.outcome_delay = data.outcome_delay.map(Set).unwrap_or(this.outcome_delay);
+        this.conditions = data.conditions.map(Set).unwrap_or(this.conditions);
+        this.user_access = data.user_access.map(Set).unwrap_or(this.user_access);
+        this.feedback_replies = data
+            .feedback_replies
+            .map(Set)
+            .unwrap_or(this.feedback_replies);
+        this.group_id = data.group_id.map(Set).unwrap_or(this.group_id);
         this.order = data.order.map(Set).unwrap_or(this.order);
 
         let this = this.update(db).await?;
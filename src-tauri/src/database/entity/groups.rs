@@ -0,0 +1,227 @@
+use super::{
+    shared::{DbResult, MinimumRequireRole, UpdateOrdering},
+    CommandColumn, CommandEntity, EventColumn, EventEntity,
+};
+use anyhow::Context;
+use chrono::Utc;
+use futures::{future::BoxFuture, stream::FuturesUnordered, TryStreamExt};
+use sea_orm::{
+    entity::prelude::*, sea_query::Func, ActiveValue::Set, IntoActiveModel, QueryOrder,
+    UpdateResult,
+};
+use serde::{Deserialize, Serialize};
+
+// Type alias helpers for the database entity types
+pub type GroupModel = Model;
+pub type GroupEntity = Entity;
+pub type GroupActiveModel = ActiveModel;
+pub type GroupColumn = Column;
+
+/// Group of events and commands that can be enabled and disabled together
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "groups")]
+pub struct Model {
+    /// Unique ID for the group
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    /// Name of the group
+    pub name: String,
+    /// Whether the events and commands in the group are enabled
+    pub enabled: bool,
+    /// Cooldown in milliseconds for events and commands in the group
+    /// that don't have their own cooldown
+    pub default_cooldown: Option<u32>,
+    /// Minimum required role for events and commands in the group
+    /// that don't require a role themselves
+    pub default_require_role: MinimumRequireRole,
+    /// Ordering
+    pub order: u32,
+    // Date time of creation
+    pub created_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateGroup {
+    pub name: String,
+    pub enabled: bool,
+    #[serde(default)]
+    pub default_cooldown: Option<u32>,
+    pub default_require_role: MinimumRequireRole,
+}
+
+#[derive(Default, Deserialize)]
+pub struct UpdateGroup {
+    pub name: Option<String>,
+    pub enabled: Option<bool>,
+    #[serde(default, deserialize_with = "super::shared::deserialize_some")]
+    pub default_cooldown: Option<Option<u32>>,
+    pub default_require_role: Option<MinimumRequireRole>,
+    pub order: Option<u32>,
+}
+
+impl Model {
+    /// Create a new group
+    pub async fn create<C>(db: &C, create: CreateGroup) -> anyhow::Result<Model>
+    where
+        C: ConnectionTrait + Send + 'static,
+    {
+        let id = Uuid::new_v4();
+        let active_model = ActiveModel {
+            id: Set(id),
+            name: Set(create.name),
+            enabled: Set(create.enabled),
+            default_cooldown: Set(create.default_cooldown),
+            default_require_role: Set(create.default_require_role),
+            order: Set(0),
+            created_at: Set(Utc::now()),
+        };
+
+        Entity::insert(active_model)
+            .exec_without_returning(db)
+            .await?;
+
+        let model = Self::get_by_id(db, id)
+            .await?
+            .context("model was not inserted")?;
+
+        Ok(model)
+    }
+
+    /// Find a specific group by ID
+    pub async fn get_by_id<C>(db: &C, id: Uuid) -> DbResult<Option<Self>>
+    where
+        C: ConnectionTrait + Send + 'static,
+    {
+        Entity::find_by_id(id).one(db).await
+    }
+
+    /// Find a group by name, ignoring case
+    pub async fn get_by_name<C>(db: &C, name: &str) -> DbResult<Option<Self>>
+    where
+        C: ConnectionTrait + Send + 'static,
+    {
+        Entity::find()
+            .filter(Expr::expr(Func::lower(Expr::col(Column::Name))).eq(name.to_lowercase()))
+            .one(db)
+            .await
+    }
+
+    /// Find all groups
+    pub async fn all<C>(db: &C) -> DbResult<Vec<Self>>
+    where
+        C: ConnectionTrait + Send + 'static,
+    {
+        Entity::find()
+            .order_by_asc(Column::Order)
+            .order_by_desc(Column::CreatedAt)
+            .all(db)
+            .await
+    }
+
+    /// Update the current group
+    pub async fn update<C>(self, db: &C, data: UpdateGroup) -> DbResult<Self>
+    where
+        C: ConnectionTrait + Send + 'static,
+    {
+        let mut this = self.into_active_model();
+
+        this.name = data.name.map(Set).unwrap_or(this.name);
+        this.enabled = data.enabled.map(Set).unwrap_or(this.enabled);
+        this.default_cooldown = data
+            .default_cooldown
+            .map(Set)
+            .unwrap_or(this.default_cooldown);
+        this.default_require_role = data
+            .default_require_role
+            .map(Set)
+            .unwrap_or(this.default_require_role);
+        this.order = data.order.map(Set).unwrap_or(this.order);
+
+        let this = this.update(db).await?;
+        Ok(this)
+    }
+
+    /// Delete the group, events and commands in the group are moved
+    /// out of the group
+    pub async fn delete_group<C>(self, db: &C) -> DbResult<()>
+    where
+        C: ConnectionTrait + Send + 'static,
+    {
+        EventEntity::update_many()
+            .filter(EventColumn::GroupId.eq(self.id))
+            .col_expr(EventColumn::GroupId, Expr::value(Option::<Uuid>::None))
+            .exec(db)
+            .await?;
+
+        CommandEntity::update_many()
+            .filter(CommandColumn::GroupId.eq(self.id))
+            .col_expr(CommandColumn::GroupId, Expr::value(Option::<Uuid>::None))
+            .exec(db)
+            .await?;
+
+        self.delete(db).await?;
+        Ok(())
+    }
+
+    pub async fn update_order<C>(db: &C, data: Vec<UpdateOrdering>) -> DbResult<()>
+    where
+        C: ConnectionTrait + Send + 'static,
+    {
+        let _results: Result<Vec<UpdateResult>, DbErr> = data
+            .into_iter()
+            .map(|data| -> BoxFuture<'_, DbResult<UpdateResult>> {
+                Box::pin(
+                    Entity::update_many()
+                        .filter(Column::Id.eq(data.id))
+                        .col_expr(Column::Order, data.order.into())
+                        .exec(db),
+                )
+            })
+            .collect::<FuturesUnordered<BoxFuture<'_, DbResult<UpdateResult>>>>()
+            .try_collect()
+            .await;
+
+        Ok(())
+    }
+
+    /// Get the role required for a member of the group, the group default
+    /// is used when the member doesn't require a role
+    pub fn get_require_role(&self, role: MinimumRequireRole) -> MinimumRequireRole {
+        match role {
+            MinimumRequireRole::None => self.default_require_role.clone(),
+            role => role,
+        }
+    }
+
+    /// Get the default cooldown duration to use for a member of the group
+    /// with the provided cooldown, [None] when the member has its own cooldown
+    /// or the group has no default
+    pub fn get_default_cooldown(&self, enabled: bool, duration: u32) -> Option<u32> {
+        if enabled && duration > 0 {
+            return None;
+        }
+
+        self.default_cooldown.filter(|duration| *duration > 0)
+    }
+
+    /// Applies the group defaults to the required role and cooldown
+    /// of a member of the group
+    pub fn apply_member_defaults(
+        &self,
+        require_role: &mut MinimumRequireRole,
+        cooldown_enabled: &mut bool,
+        cooldown_duration: &mut u32,
+    ) {
+        *require_role = self.get_require_role(require_role.clone());
+
+        if let Some(duration) = self.get_default_cooldown(*cooldown_enabled, *cooldown_duration) {
+            *cooldown_enabled = true;
+            *cooldown_duration = duration;
+        }
+    }
+}
@@ -4,11 +4,13 @@ pub mod commands;
 pub mod event_executions;
 pub mod event_logs;
 pub mod events;
+pub mod groups;
 pub mod items;
 pub mod items_impact_sounds;
 pub mod key_value;
 pub mod links;
 pub mod model_data;
+pub mod seen_viewers;
 pub mod shared;
 pub mod sounds;
 
@@ -30,6 +32,9 @@ pub use commands::{CommandActiveModel, CommandColumn, CommandEntity, CommandMode
 #[allow(unused)]
 pub use events::{EventActiveModel, EventColumn, EventEntity, EventModel};
 
+#[allow(unused)]
+pub use groups::{GroupActiveModel, GroupColumn, GroupEntity, GroupModel};
+
 #[allow(unused)]
 pub use model_data::{ModelDataActiveModel, ModelDataColumn, ModelDataEntity, ModelDataModel};
 
@@ -54,3 +59,8 @@ pub use key_value::{KeyValueActiveModel, KeyValueColumn, KeyValueEntity, KeyValu
 
 #[allow(unused)]
 pub use event_logs::{EventLogsActiveModel, EventLogsColumn, EventLogsEntity, EventLogsModel};
+
+#[allow(unused)]
+pub use seen_viewers::{
+    SeenViewerActiveModel, SeenViewerColumn, SeenViewerEntity, SeenViewerModel,
+};
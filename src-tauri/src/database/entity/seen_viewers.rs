@@ -0,0 +1,106 @@
+//! # Seen Viewers
+//!
+//! Tracks viewers that have been seen chatting in the channel
+
+use sea_orm::{entity::prelude::*, sea_query::OnConflict, ActiveValue::Set};
+use serde::{Deserialize, Serialize};
+
+use super::shared::DbResult;
+
+// Type alias helpers for the database entity types
+pub type SeenViewerModel = Model;
+pub type SeenViewerEntity = Entity;
+pub type SeenViewerActiveModel = ActiveModel;
+pub type SeenViewerColumn = Column;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "seen_viewers")]
+pub struct Model {
+    /// Twitch user ID of the viewer
+    #[sea_orm(primary_key)]
+    pub user_id: String,
+    /// When the viewer was first seen chatting
+    pub first_seen_at: DateTimeUtc,
+    /// When the viewer was last seen chatting
+    pub last_seen_at: DateTimeUtc,
+    /// ID of the stream the viewer was last seen chatting in
+    pub last_stream_id: Option<String>,
+    /// ID of the stream the viewer was first seen chatting in
+    pub first_stream_id: Option<String>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Model {
+    /// Records a chat message from the viewer with the `user_id` during
+    /// the stream with `stream_id`
+    ///
+    /// Returns the previous state of the viewer, [None] if the viewer
+    /// has not been seen before
+    pub async fn record_message<C>(
+        db: &C,
+        user_id: &str,
+        stream_id: Option<&str>,
+        current_time: DateTimeUtc,
+    ) -> DbResult<Option<Self>>
+    where
+        C: ConnectionTrait + Send + 'static,
+    {
+        let previous = Self::get_by_id(db, user_id).await?;
+
+        let first_stream_id = stream_id.map(|value| value.to_string());
+
+        // Keep the previous stream ID if the stream is unknown
+        let stream_id = first_stream_id.clone().or_else(|| {
+            previous
+                .as_ref()
+                .and_then(|previous| previous.last_stream_id.clone())
+        });
+
+        let active_model = ActiveModel {
+            user_id: Set(user_id.to_string()),
+            first_seen_at: Set(current_time),
+            last_seen_at: Set(current_time),
+            last_stream_id: Set(stream_id),
+            first_stream_id: Set(first_stream_id),
+        };
+
+        Entity::insert(active_model)
+            .on_conflict(
+                OnConflict::column(Column::UserId)
+                    .update_column(Column::LastSeenAt)
+                    .update_column(Column::LastStreamId)
+                    .to_owned(),
+            )
+            .exec_without_returning(db)
+            .await?;
+
+        Ok(previous)
+    }
+
+    /// Checks if any viewer was first seen during a stream other than
+    /// the stream with `stream_id`
+    pub async fn has_viewer_from_other_stream<C>(db: &C, stream_id: Option<&str>) -> DbResult<bool>
+    where
+        C: ConnectionTrait + Send + 'static,
+    {
+        let mut query = Entity::find().filter(Column::FirstStreamId.is_not_null());
+
+        if let Some(stream_id) = stream_id {
+            query = query.filter(Column::FirstStreamId.ne(stream_id));
+        }
+
+        Ok(query.one(db).await?.is_some())
+    }
+
+    /// Find a specific seen viewer by user ID
+    pub async fn get_by_id<C>(db: &C, user_id: &str) -> DbResult<Option<Self>>
+    where
+        C: ConnectionTrait + Send + 'static,
+    {
+        Entity::find_by_id(user_id).one(db).await
+    }
+}
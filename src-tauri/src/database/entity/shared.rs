@@ -1,6 +1,6 @@
 use sea_orm::prelude::*;
-use sea_orm::{DeriveActiveEnum, EnumIter};
-use serde::{Deserialize, Serialize};
+use sea_orm::{DeriveActiveEnum, EnumIter, FromJsonQueryResult};
+use serde::{Deserialize, Deserializer, Serialize};
 
 pub type DbResult<T> = Result<T, DbErr>;
 
@@ -11,6 +11,15 @@ pub enum MinimumRequireRole {
     None,
     #[sea_orm(string_value = "Follower")]
     Follower,
+    /// Subscribed at any tier
+    #[sea_orm(string_value = "Subscriber")]
+    Subscriber,
+    /// Subscribed at tier 2 or above
+    #[sea_orm(string_value = "SubscriberTier2")]
+    SubscriberTier2,
+    /// Subscribed at tier 3
+    #[sea_orm(string_value = "SubscriberTier3")]
+    SubscriberTier3,
     #[sea_orm(string_value = "Vip")]
     Vip,
     #[sea_orm(string_value = "Mod")]
@@ -19,6 +28,49 @@ pub enum MinimumRequireRole {
     Broadcaster,
 }
 
+/// Per-user access lists, allowed users bypass the required role
+/// and denied users are never able to trigger
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, FromJsonQueryResult)]
+#[serde(default)]
+pub struct UserAccessList {
+    /// Users allowed regardless of their role
+    pub allowed: Vec<UserAccessEntry>,
+    /// Users that are blocked from triggering
+    pub denied: Vec<UserAccessEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UserAccessEntry {
+    /// ID of the user
+    pub user_id: String,
+    /// Display name of the user at the time they were added
+    pub display_name: String,
+}
+
+impl UserAccessList {
+    /// Check if the user is on the allow list
+    pub fn is_allowed(&self, user_id: &str) -> bool {
+        self.allowed.iter().any(|entry| entry.user_id == user_id)
+    }
+
+    /// Check if the user is on the deny list
+    pub fn is_denied(&self, user_id: &str) -> bool {
+        self.denied.iter().any(|entry| entry.user_id == user_id)
+    }
+}
+
+/// Optional chat replies sent when an event or command is skipped
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, FromJsonQueryResult)]
+#[serde(default)]
+pub struct FeedbackReplies {
+    /// Reply when on cooldown, supports $(user) and $(remaining)
+    pub cooldown: Option<String>,
+    /// Reply when missing the required role, supports $(user) and $(role)
+    pub missing_role: Option<String>,
+    /// Reply when disabled, supports $(user)
+    pub disabled: Option<String>,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct MinMax<T> {
     /// Minimum value
@@ -62,3 +114,13 @@ pub struct UpdateOrdering {
     pub id: Uuid,
     pub order: u32,
 }
+
+/// Any value that is present is considered Some value, including null. Used for
+/// updating optional fields where null clears the value
+pub fn deserialize_some<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    Deserialize::deserialize(deserializer).map(Some)
+}
@@ -0,0 +1,39 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(SeenViewers::Table)
+                    .if_not_exists()
+                    .col(string(SeenViewers::UserId).primary_key())
+                    .col(date_time(SeenViewers::FirstSeenAt))
+                    .col(date_time(SeenViewers::LastSeenAt))
+                    .col(string_null(SeenViewers::LastStreamId))
+                    .col(string_null(SeenViewers::FirstStreamId))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(SeenViewers::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum SeenViewers {
+    Table,
+    UserId,
+    FirstSeenAt,
+    LastSeenAt,
+    LastStreamId,
+    FirstStreamId,
+}
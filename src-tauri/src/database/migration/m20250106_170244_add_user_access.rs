@@ -0,0 +1,61 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+const DEFAULT_USER_ACCESS: &str = r#"{"allowed":[],"denied":[]}"#;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Events::Table)
+                    .add_column(json(Events::UserAccess).default(DEFAULT_USER_ACCESS))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Commands::Table)
+                    .add_column(json(Commands::UserAccess).default(DEFAULT_USER_ACCESS))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Events::Table)
+                    .drop_column(Events::UserAccess)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Commands::Table)
+                    .drop_column(Commands::UserAccess)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Events {
+    Table,
+    UserAccess,
+}
+
+#[derive(DeriveIden)]
+enum Commands {
+    Table,
+    UserAccess,
+}
@@ -0,0 +1,91 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Groups::Table)
+                    .if_not_exists()
+                    .col(pk_uuid(Groups::Id))
+                    .col(string(Groups::Name))
+                    .col(boolean(Groups::Enabled))
+                    .col(integer_null(Groups::DefaultCooldown))
+                    .col(string(Groups::DefaultRequireRole))
+                    .col(integer(Groups::Order))
+                    .col(date_time(Groups::CreatedAt))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Events::Table)
+                    .add_column(uuid_null(Events::GroupId))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Commands::Table)
+                    .add_column(uuid_null(Commands::GroupId))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Events::Table)
+                    .drop_column(Events::GroupId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Commands::Table)
+                    .drop_column(Commands::GroupId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(Groups::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Groups {
+    Table,
+    Id,
+    Name,
+    Enabled,
+    DefaultCooldown,
+    DefaultRequireRole,
+    Order,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Events {
+    Table,
+    GroupId,
+}
+
+#[derive(DeriveIden)]
+enum Commands {
+    Table,
+    GroupId,
+}
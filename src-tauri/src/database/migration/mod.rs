@@ -12,6 +12,11 @@ mod m20241211_102725_seed_defaults;
 mod m20241212_114700_create_key_value_table;
 mod m20241214_080902_create_command_logs_table;
 mod m20241227_110419_create_event_logs_table;
+mod m20250104_093012_add_event_conditions;
+mod m20250105_141522_create_seen_viewers_table;
+mod m20250106_170244_add_user_access;
+mod m20250107_192631_add_feedback_replies;
+mod m20250108_154810_create_groups_table;
 
 pub struct Migrator;
 
@@ -31,6 +36,11 @@ impl MigratorTrait for Migrator {
             Box::new(m20241212_114700_create_key_value_table::Migration),
             Box::new(m20241214_080902_create_command_logs_table::Migration),
             Box::new(m20241227_110419_create_event_logs_table::Migration),
+            Box::new(m20250104_093012_add_event_conditions::Migration),
+            Box::new(m20250105_141522_create_seen_viewers_table::Migration),
+            Box::new(m20250106_170244_add_user_access::Migration),
+            Box::new(m20250107_192631_add_feedback_replies::Migration),
+            Box::new(m20250108_154810_create_groups_table::Migration),
         ]
     }
 }
@@ -0,0 +1,122 @@
+//! # Chat Activity
+//!
+//! Tracks chat activity, used to prevent timer events from running
+//! when chat has been inactive
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use twitch_api::types::UserId;
+use uuid::Uuid;
+
+/// Duration chatters are remembered for when computing unique chatters
+const CHATTER_RETENTION: Duration = Duration::from_secs(60 * 60 * 12);
+
+/// Shared tracker for chat activity
+#[derive(Clone, Default)]
+pub struct ChatActivityTracker {
+    inner: Arc<Mutex<ChatActivityState>>,
+}
+
+#[derive(Default)]
+struct ChatActivityState {
+    /// Total number of chat messages received
+    total_messages: u64,
+    /// Last time each chatter sent a message
+    chatters: HashMap<UserId, Instant>,
+    /// Activity at the time each event last ran
+    last_runs: HashMap<Uuid, ChatActivityRun>,
+}
+
+/// Snapshot of the chat activity when an event ran
+struct ChatActivityRun {
+    /// Total number of messages at the time of the run
+    total_messages: u64,
+    /// Time of the run
+    time: Instant,
+}
+
+/// Chat activity since an event last ran
+#[derive(Debug, Clone, Copy)]
+pub struct ChatActivity {
+    /// Number of chat messages sent
+    pub messages: u64,
+    /// Number of unique chatters who sent messages
+    pub unique_chatters: u64,
+}
+
+impl ChatActivityTracker {
+    /// Records a new chat message from the user with `user_id`
+    pub fn record_message(&self, user_id: UserId) {
+        let state = &mut *match self.inner.lock() {
+            Ok(value) => value,
+            Err(_) => return,
+        };
+
+        let now = Instant::now();
+
+        state.total_messages += 1;
+        state.chatters.insert(user_id, now);
+
+        // Forget chatters that haven't chatted in a long time
+        state
+            .chatters
+            .retain(|_, last_message| now.duration_since(*last_message) < CHATTER_RETENTION);
+    }
+
+    /// Get the chat activity since the event with `event_id` last ran,
+    /// includes all activity when the event has not yet run
+    pub fn get_activity_since_last_run(&self, event_id: Uuid) -> ChatActivity {
+        let state = &*match self.inner.lock() {
+            Ok(value) => value,
+            Err(_) => {
+                return ChatActivity {
+                    messages: 0,
+                    unique_chatters: 0,
+                }
+            }
+        };
+
+        let last_run = state.last_runs.get(&event_id);
+
+        let messages = match last_run {
+            Some(last_run) => state.total_messages - last_run.total_messages,
+            None => state.total_messages,
+        };
+
+        let unique_chatters = match last_run {
+            Some(last_run) => state
+                .chatters
+                .values()
+                .filter(|last_message| **last_message > last_run.time)
+                .count(),
+            None => state.chatters.len(),
+        } as u64;
+
+        ChatActivity {
+            messages,
+            unique_chatters,
+        }
+    }
+
+    /// Marks the event with `event_id` as having run, resetting the
+    /// activity for the event
+    pub fn mark_event_run(&self, event_id: Uuid) {
+        let state = &mut *match self.inner.lock() {
+            Ok(value) => value,
+            Err(_) => return,
+        };
+
+        let total_messages = state.total_messages;
+        state.last_runs.insert(
+            event_id,
+            ChatActivityRun {
+                total_messages,
+                time: Instant::now(),
+            },
+        );
+    }
+}
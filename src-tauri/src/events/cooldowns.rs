@@ -0,0 +1,507 @@
+//! # Cooldowns
+//!
+//! In-memory index of the last execution times for events and commands,
+//! used to check cooldowns without paging through the executions tables
+//!
+//! Only executions that are still within their cooldown are kept, entries
+//! are removed once the cooldown they were recorded with has elapsed
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use chrono::TimeDelta;
+use sea_orm::{prelude::DateTimeUtc, DatabaseConnection};
+use twitch_api::types::UserId;
+use uuid::Uuid;
+
+use crate::database::entity::{
+    CommandExecutionModel, CommandModel, EventExecutionModel, EventModel, GroupModel,
+};
+
+/// Target a cooldown applies to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CooldownTarget {
+    Event(Uuid),
+    Command(Uuid),
+}
+
+/// Shared tracker for event and command cooldowns
+#[derive(Clone, Default)]
+pub struct CooldownTracker {
+    inner: Arc<Mutex<CooldownState>>,
+}
+
+#[derive(Default)]
+struct CooldownState {
+    /// Last execution for each target
+    last_executions: HashMap<CooldownTarget, CooldownEntry>,
+    /// Last execution for each user of each target
+    last_user_executions: HashMap<(CooldownTarget, UserId), CooldownEntry>,
+}
+
+/// Recorded execution of a target
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct CooldownEntry {
+    /// When the execution happened
+    time: DateTimeUtc,
+    /// When the cooldown the execution was recorded with elapses
+    expires_at: DateTimeUtc,
+}
+
+/// Execution recorded by [CooldownTracker::try_record_execution], can be
+/// rolled back if the execution did not go ahead
+#[must_use]
+pub struct CooldownCharge {
+    target: CooldownTarget,
+    user_id: Option<UserId>,
+    /// When the execution was recorded
+    time: DateTimeUtc,
+    /// Entries replaced by the execution
+    previous: Option<CooldownEntry>,
+    previous_user: Option<CooldownEntry>,
+}
+
+impl CooldownTracker {
+    /// Creates a new tracker, rebuilding the last execution times from
+    /// any executions that could still be within their cooldown
+    pub async fn load(db: &DatabaseConnection, current_time: DateTimeUtc) -> anyhow::Result<Self> {
+        let tracker = Self::default();
+        tracker.reload(db, current_time).await?;
+        Ok(tracker)
+    }
+
+    /// Rebuilds the last execution times from the executions that are
+    /// still within their cooldown, replacing the current state
+    pub async fn reload(
+        &self,
+        db: &DatabaseConnection,
+        current_time: DateTimeUtc,
+    ) -> anyhow::Result<()> {
+        let events = EventModel::all(db).await?;
+        let commands = CommandModel::all(db).await?;
+        let groups: HashMap<Uuid, GroupModel> = GroupModel::all(db)
+            .await?
+            .into_iter()
+            .map(|group| (group.id, group))
+            .collect();
+
+        // Cooldown duration for a target including the defaults from its group
+        let get_duration = |group_id: Option<Uuid>, enabled: bool, duration: u32| {
+            let group_duration = group_id
+                .and_then(|group_id| groups.get(&group_id))
+                .and_then(|group| group.get_default_cooldown(enabled, duration));
+
+            match group_duration {
+                Some(duration) => duration,
+                None if enabled => duration,
+                None => 0,
+            }
+        };
+
+        let durations: HashMap<CooldownTarget, u32> = events
+            .iter()
+            .map(|event| {
+                (
+                    CooldownTarget::Event(event.id),
+                    get_duration(
+                        event.group_id,
+                        event.cooldown.enabled,
+                        event.cooldown.duration,
+                    ),
+                )
+            })
+            .chain(commands.iter().map(|command| {
+                (
+                    CooldownTarget::Command(command.id),
+                    get_duration(
+                        command.group_id,
+                        command.cooldown.enabled,
+                        command.cooldown.duration,
+                    ),
+                )
+            }))
+            .filter(|(_, duration)| *duration > 0)
+            .collect();
+
+        // Only executions within the longest cooldown need to be loaded
+        let max_cooldown = durations.values().copied().max().unwrap_or_default();
+        let start_date = current_time - TimeDelta::milliseconds(max_cooldown as i64);
+
+        let mut state = CooldownState::default();
+
+        // Executions are loaded in order so later executions replace earlier ones
+        for execution in EventExecutionModel::get_since(db, start_date).await? {
+            // Failed executions did not use up the cooldown
+            if execution
+                .metadata
+                .data
+                .iter()
+                .any(|(key, _)| key == "error")
+            {
+                continue;
+            }
+
+            let target = CooldownTarget::Event(execution.event_id);
+            if let Some(duration) = durations.get(&target) {
+                state.record_execution(
+                    target,
+                    execution.metadata.user.map(|user| user.id).as_ref(),
+                    *duration,
+                    execution.created_at,
+                );
+            }
+        }
+
+        for execution in CommandExecutionModel::get_since(db, start_date).await? {
+            let target = CooldownTarget::Command(execution.command_id);
+            if let Some(duration) = durations.get(&target) {
+                state.record_execution(
+                    target,
+                    execution.metadata.user.map(|user| user.id).as_ref(),
+                    *duration,
+                    execution.created_at,
+                );
+            }
+        }
+
+        state.prune(current_time);
+
+        *self.inner.lock().expect("cooldown state lock poisoned") = state;
+
+        Ok(())
+    }
+
+    /// Records an execution of the target if its cooldown has elapsed,
+    /// otherwise the remaining cooldown is returned as the error
+    ///
+    /// The check and record happen under the same lock so concurrent
+    /// executions cannot both pass the cooldown. Nothing is recorded
+    /// without a cooldown
+    pub fn try_record_execution(
+        &self,
+        target: CooldownTarget,
+        user_id: Option<&UserId>,
+        duration: u32,
+        per_user: bool,
+        current_time: DateTimeUtc,
+    ) -> Result<Option<CooldownCharge>, Duration> {
+        let state = &mut *self.inner.lock().expect("cooldown state lock poisoned");
+        state.prune(current_time);
+
+        if duration == 0 {
+            return Ok(None);
+        }
+
+        if let Some(remaining) =
+            state.get_remaining(target, user_id, duration, per_user, current_time)
+        {
+            return Err(remaining);
+        }
+
+        let (previous, previous_user) =
+            state.record_execution(target, user_id, duration, current_time);
+
+        Ok(Some(CooldownCharge {
+            target,
+            user_id: user_id.cloned(),
+            time: current_time,
+            previous,
+            previous_user,
+        }))
+    }
+
+    /// Rolls back an execution recorded by [CooldownTracker::try_record_execution]
+    /// restoring the previous execution, executions recorded after the charge
+    /// are kept
+    pub fn rollback(&self, charge: CooldownCharge) {
+        let state = &mut *self.inner.lock().expect("cooldown state lock poisoned");
+
+        restore_entry(
+            &mut state.last_executions,
+            charge.target,
+            charge.time,
+            charge.previous,
+        );
+
+        if let Some(user_id) = charge.user_id {
+            restore_entry(
+                &mut state.last_user_executions,
+                (charge.target, user_id),
+                charge.time,
+                charge.previous_user,
+            );
+        }
+    }
+
+    /// Get the remaining time before the cooldown for the target has
+    /// elapsed, [None] when the cooldown has already elapsed
+    ///
+    /// Per user cooldowns are always elapsed for anonymous users
+    pub fn get_remaining(
+        &self,
+        target: CooldownTarget,
+        user_id: Option<&UserId>,
+        duration: u32,
+        per_user: bool,
+        current_time: DateTimeUtc,
+    ) -> Option<Duration> {
+        let state = &*self.inner.lock().expect("cooldown state lock poisoned");
+        state.get_remaining(target, user_id, duration, per_user, current_time)
+    }
+}
+
+/// Restores the entry replaced by a charge, the entry is left alone if it
+/// was replaced by a later execution
+fn restore_entry<K>(
+    entries: &mut HashMap<K, CooldownEntry>,
+    key: K,
+    time: DateTimeUtc,
+    previous: Option<CooldownEntry>,
+) where
+    K: std::hash::Hash + Eq,
+{
+    if entries.get(&key).is_none_or(|entry| entry.time != time) {
+        return;
+    }
+
+    match previous {
+        Some(previous) => entries.insert(key, previous),
+        None => entries.remove(&key),
+    };
+}
+
+impl CooldownState {
+    /// Records an execution, returns the entries that were replaced
+    fn record_execution(
+        &mut self,
+        target: CooldownTarget,
+        user_id: Option<&UserId>,
+        duration: u32,
+        time: DateTimeUtc,
+    ) -> (Option<CooldownEntry>, Option<CooldownEntry>) {
+        let entry = CooldownEntry {
+            time,
+            expires_at: time + TimeDelta::milliseconds(duration as i64),
+        };
+
+        let previous = self.last_executions.insert(target, entry);
+        let previous_user = user_id.and_then(|user_id| {
+            self.last_user_executions
+                .insert((target, user_id.clone()), entry)
+        });
+
+        (previous, previous_user)
+    }
+
+    /// Removes entries where the cooldown has elapsed
+    fn prune(&mut self, current_time: DateTimeUtc) {
+        self.last_executions
+            .retain(|_, entry| entry.expires_at > current_time);
+        self.last_user_executions
+            .retain(|_, entry| entry.expires_at > current_time);
+    }
+
+    fn get_remaining(
+        &self,
+        target: CooldownTarget,
+        user_id: Option<&UserId>,
+        duration: u32,
+        per_user: bool,
+        current_time: DateTimeUtc,
+    ) -> Option<Duration> {
+        let last_execution = if per_user {
+            let user_id = user_id?;
+            self.last_user_executions
+                .get(&(target, user_id.clone()))?
+                .time
+        } else {
+            self.last_executions.get(&target)?.time
+        };
+
+        let cooldown_end_time = last_execution + TimeDelta::milliseconds(duration as i64);
+
+        (cooldown_end_time - current_time)
+            .to_std()
+            .ok()
+            .filter(|remaining| !remaining.is_zero())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::{TimeDelta, Utc};
+    use twitch_api::types::UserId;
+    use uuid::Uuid;
+
+    use super::{CooldownState, CooldownTarget, CooldownTracker};
+
+    #[test]
+    fn test_global_cooldown_remaining() {
+        let tracker = CooldownTracker::default();
+        let target = CooldownTarget::Event(Uuid::new_v4());
+        let current_time = Utc::now();
+
+        assert_eq!(
+            tracker.get_remaining(target, None, 5000, false, current_time),
+            None
+        );
+
+        _ = tracker
+            .try_record_execution(
+                target,
+                None,
+                5000,
+                false,
+                current_time - TimeDelta::seconds(2),
+            )
+            .unwrap();
+
+        let remaining = tracker
+            .get_remaining(target, None, 5000, false, current_time)
+            .unwrap();
+        assert_eq!(remaining.as_secs(), 3);
+
+        assert_eq!(
+            tracker.get_remaining(target, None, 1000, false, current_time),
+            None
+        );
+    }
+
+    #[test]
+    fn test_per_user_cooldown_remaining() {
+        let tracker = CooldownTracker::default();
+        let target = CooldownTarget::Command(Uuid::new_v4());
+        let current_time = Utc::now();
+
+        let user_a = UserId::from_static("1");
+        let user_b = UserId::from_static("2");
+
+        _ = tracker
+            .try_record_execution(target, Some(&user_a), 5000, true, current_time)
+            .unwrap();
+
+        assert!(tracker
+            .get_remaining(target, Some(&user_a), 5000, true, current_time)
+            .is_some());
+        assert_eq!(
+            tracker.get_remaining(target, Some(&user_b), 5000, true, current_time),
+            None
+        );
+
+        // Anonymous users bypass per user cooldowns
+        assert_eq!(
+            tracker.get_remaining(target, None, 5000, true, current_time),
+            None
+        );
+    }
+
+    #[test]
+    fn test_try_record_execution() {
+        let tracker = CooldownTracker::default();
+        let target = CooldownTarget::Event(Uuid::new_v4());
+        let current_time = Utc::now();
+
+        assert!(tracker
+            .try_record_execution(target, None, 5000, false, current_time)
+            .is_ok());
+
+        // Second execution is within the cooldown recorded by the first
+        let remaining = tracker
+            .try_record_execution(target, None, 5000, false, current_time)
+            .err()
+            .unwrap();
+        assert_eq!(remaining.as_secs(), 5);
+
+        assert!(tracker
+            .try_record_execution(
+                target,
+                None,
+                5000,
+                false,
+                current_time + TimeDelta::seconds(5)
+            )
+            .is_ok());
+    }
+
+    #[test]
+    fn test_zero_cooldown_not_recorded() {
+        let tracker = CooldownTracker::default();
+        let target = CooldownTarget::Event(Uuid::new_v4());
+        let current_time = Utc::now();
+
+        let charge = tracker
+            .try_record_execution(target, None, 0, false, current_time)
+            .unwrap();
+        assert!(charge.is_none());
+
+        let state = &*tracker.inner.lock().unwrap();
+        assert!(state.last_executions.is_empty());
+    }
+
+    #[test]
+    fn test_prune_expired() {
+        let mut state = CooldownState::default();
+        let target = CooldownTarget::Event(Uuid::new_v4());
+        let user = UserId::from_static("1");
+        let current_time = Utc::now();
+
+        _ = state.record_execution(target, Some(&user), 5000, current_time);
+
+        state.prune(current_time + TimeDelta::seconds(4));
+        assert_eq!(state.last_executions.len(), 1);
+        assert_eq!(state.last_user_executions.len(), 1);
+
+        state.prune(current_time + TimeDelta::seconds(5));
+        assert!(state.last_executions.is_empty());
+        assert!(state.last_user_executions.is_empty());
+    }
+
+    #[test]
+    fn test_rollback() {
+        let tracker = CooldownTracker::default();
+        let target = CooldownTarget::Event(Uuid::new_v4());
+        let user_a = UserId::from_static("1");
+        let user_b = UserId::from_static("2");
+        let current_time = Utc::now();
+        let later = current_time + TimeDelta::seconds(1);
+
+        let first = tracker
+            .try_record_execution(target, Some(&user_a), 10_000, true, current_time)
+            .unwrap()
+            .unwrap();
+        let second = tracker
+            .try_record_execution(target, Some(&user_b), 10_000, true, later)
+            .unwrap()
+            .unwrap();
+
+        // Rolling back the second execution restores the first
+        tracker.rollback(second);
+        assert_eq!(
+            tracker
+                .get_remaining(target, None, 10_000, false, later)
+                .map(|remaining| remaining.as_secs()),
+            Some(9)
+        );
+        assert_eq!(
+            tracker.get_remaining(target, Some(&user_b), 10_000, true, later),
+            None
+        );
+        assert!(tracker
+            .get_remaining(target, Some(&user_a), 10_000, true, later)
+            .is_some());
+
+        // Rolling back the first execution clears the cooldown
+        tracker.rollback(first);
+        assert_eq!(
+            tracker.get_remaining(target, None, 10_000, false, later),
+            None
+        );
+        assert_eq!(
+            tracker.get_remaining(target, Some(&user_a), 10_000, true, later),
+            None
+        );
+    }
+}
@@ -0,0 +1,180 @@
+//! # Feedback
+//!
+//! Chat replies sent when an event or command is skipped, rate limited
+//! per event or command to prevent spamming chat
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use anyhow::Context;
+use log::error;
+use sea_orm::DatabaseConnection;
+
+use crate::{
+    database::entity::shared::{FeedbackReplies, MinimumRequireRole},
+    twitch::manager::{TwitchEventUser, TwitchManager},
+};
+
+use super::{
+    cooldowns::CooldownTarget,
+    template::{render_template, validate_template, TemplateContext, TemplateVariables},
+};
+
+/// Minimum time between feedback replies for the same event or command
+const FEEDBACK_REPLY_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Shared rate limiter for feedback replies
+#[derive(Clone, Default)]
+pub struct FeedbackLimiter {
+    inner: Arc<Mutex<HashMap<CooldownTarget, Instant>>>,
+}
+
+impl FeedbackLimiter {
+    /// Attempts to reserve a reply for the target, returns false if
+    /// a reply was sent for the target too recently
+    fn try_reserve(&self, target: CooldownTarget) -> bool {
+        let last_replies = &mut *self.inner.lock().expect("feedback state lock poisoned");
+
+        // Remove expired entries
+        last_replies.retain(|_, last_reply| last_reply.elapsed() < FEEDBACK_REPLY_INTERVAL);
+
+        if last_replies.contains_key(&target) {
+            return false;
+        }
+
+        last_replies.insert(target, Instant::now());
+        true
+    }
+}
+
+/// Reason an event or command was skipped
+pub enum FeedbackReason<'a> {
+    /// Still on cooldown for the remaining duration
+    Cooldown { remaining: Duration },
+    /// User is missing the required role
+    MissingRole { role: &'a MinimumRequireRole },
+    /// Event or command is disabled
+    Disabled,
+}
+
+/// Sends the feedback reply for the skip reason if one is configured
+/// and a reply has not been sent for the target too recently
+pub async fn send_feedback_reply(
+    db: &DatabaseConnection,
+    twitch_manager: &TwitchManager,
+    limiter: &FeedbackLimiter,
+    target: CooldownTarget,
+    replies: &FeedbackReplies,
+    user: Option<&TwitchEventUser>,
+    reason: FeedbackReason<'_>,
+) {
+    // Replies are only sent to users
+    let user = match user {
+        Some(value) => value,
+        None => return,
+    };
+
+    let template = match &reason {
+        FeedbackReason::Cooldown { .. } => replies.cooldown.as_ref(),
+        FeedbackReason::MissingRole { .. } => replies.missing_role.as_ref(),
+        FeedbackReason::Disabled => replies.disabled.as_ref(),
+    };
+
+    let template = match template {
+        Some(value) if !value.trim().is_empty() => value,
+        _ => return,
+    };
+
+    if !limiter.try_reserve(target) {
+        return;
+    }
+
+    let mut ctx = TemplateContext {
+        variables: TemplateVariables::Feedback,
+        user: Some(user),
+        ..Default::default()
+    };
+
+    match reason {
+        FeedbackReason::Cooldown { remaining } => {
+            ctx.remaining = Some(format_remaining(remaining));
+        }
+        FeedbackReason::MissingRole { role } => {
+            ctx.role = Some(get_role_name(role));
+        }
+        FeedbackReason::Disabled => {}
+    }
+
+    let message = render_template(db, template, &ctx).await;
+
+    if let Err(err) = twitch_manager.send_chat_message(&message).await {
+        error!("failed to send feedback reply: {err:?}");
+    }
+}
+
+/// Validates the templates for each of the feedback replies
+pub fn validate_feedback_replies(replies: &FeedbackReplies) -> anyhow::Result<()> {
+    let templates = [
+        (&replies.cooldown, "cooldown"),
+        (&replies.missing_role, "missing role"),
+        (&replies.disabled, "disabled"),
+    ];
+
+    for (template, name) in templates {
+        if let Some(template) = template {
+            validate_template(template, TemplateVariables::Feedback)
+                .with_context(|| format!("invalid {name} feedback reply"))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Formats the remaining duration for use in chat messages,
+/// seconds are rounded up
+fn format_remaining(remaining: Duration) -> String {
+    let mut seconds = remaining.as_secs();
+    if remaining.subsec_nanos() > 0 {
+        seconds += 1;
+    }
+
+    let minutes = seconds / 60;
+    let seconds = seconds % 60;
+
+    match (minutes, seconds) {
+        (0, seconds) => format!("{seconds}s"),
+        (minutes, 0) => format!("{minutes}m"),
+        (minutes, seconds) => format!("{minutes}m {seconds}s"),
+    }
+}
+
+/// Get a display name for the role for use in chat messages
+fn get_role_name(role: &MinimumRequireRole) -> &'static str {
+    match role {
+        MinimumRequireRole::None => "viewer",
+        MinimumRequireRole::Follower => "follower",
+        MinimumRequireRole::Subscriber => "subscriber",
+        MinimumRequireRole::SubscriberTier2 => "tier 2 subscriber",
+        MinimumRequireRole::SubscriberTier3 => "tier 3 subscriber",
+        MinimumRequireRole::Vip => "VIP",
+        MinimumRequireRole::Mod => "moderator",
+        MinimumRequireRole::Broadcaster => "broadcaster",
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::format_remaining;
+
+    #[test]
+    fn test_format_remaining() {
+        assert_eq!(format_remaining(Duration::from_millis(11_200)), "12s");
+        assert_eq!(format_remaining(Duration::from_secs(60)), "1m");
+        assert_eq!(format_remaining(Duration::from_secs(125)), "2m 5s");
+    }
+}
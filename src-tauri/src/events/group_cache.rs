@@ -0,0 +1,67 @@
+//! # Group Cache
+//!
+//! Cache of the event and command groups, used to apply the group defaults
+//! when running events and commands without loading the group every run
+
+use std::{collections::HashMap, sync::Arc};
+
+use sea_orm::DatabaseConnection;
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use crate::database::entity::{shared::DbResult, GroupModel};
+
+/// Shared cache of all groups, must be invalidated whenever a group changes
+#[derive(Clone, Default)]
+pub struct GroupCache {
+    inner: Arc<Mutex<Option<Arc<HashMap<Uuid, GroupModel>>>>>,
+}
+
+impl GroupCache {
+    /// Get a group by ID, all groups are loaded when the cache is empty
+    pub async fn get(
+        &self,
+        db: &DatabaseConnection,
+        group_id: Uuid,
+    ) -> DbResult<Option<GroupModel>> {
+        let groups = self.load(db).await?;
+        Ok(groups.get(&group_id).cloned())
+    }
+
+    /// Get the group with the optional `group_id`, [None] when there
+    /// is no group
+    pub async fn get_optional(
+        &self,
+        db: &DatabaseConnection,
+        group_id: Option<Uuid>,
+    ) -> DbResult<Option<GroupModel>> {
+        match group_id {
+            Some(group_id) => self.get(db, group_id).await,
+            None => Ok(None),
+        }
+    }
+
+    /// Clears the cache, the groups will be loaded again on next use
+    pub async fn invalidate(&self) {
+        *self.inner.lock().await = None;
+    }
+
+    /// Loads the groups from the database if they are not already cached,
+    /// the lock is held while loading so invalidating waits for any
+    /// in progress load
+    async fn load(&self, db: &DatabaseConnection) -> DbResult<Arc<HashMap<Uuid, GroupModel>>> {
+        let groups = &mut *self.inner.lock().await;
+
+        if let Some(groups) = groups.as_ref() {
+            return Ok(groups.clone());
+        }
+
+        let loaded: HashMap<Uuid, GroupModel> = GroupModel::all(db)
+            .await?
+            .into_iter()
+            .map(|group| (group.id, group))
+            .collect();
+
+        Ok(groups.insert(Arc::new(loaded)).clone())
+    }
+}
@@ -1,24 +1,45 @@
+use std::{
+    collections::HashMap,
+    sync::{LazyLock, Mutex},
+};
+
+use anyhow::Context;
+use chrono::Utc;
 use log::error;
+use regex::{Regex, RegexBuilder};
 use sea_orm::DatabaseConnection;
 use serde::{Deserialize, Deserializer, Serialize};
 use tokio::join;
 use twitch_api::{
-    eventsub::channel::chat::Fragment,
-    types::{MsgId, SubscriptionTier},
+    eventsub::channel::{chat::Fragment, hypetrain::ContributionType},
+    types::{MsgId, RedemptionId, SubscriptionTier},
 };
 
 use crate::{
     database::entity::{
-        events::{EventTrigger, EventTriggerType},
+        events::{
+            ChatMessageMatcher, EventCondition, EventConditions, EventTrigger, EventTriggerType,
+        },
         CommandModel, EventModel,
     },
     twitch::manager::{
         TwitchEventAdBreakBegin, TwitchEventChatMsg, TwitchEventCheerBits, TwitchEventFollow,
-        TwitchEventGiftSub, TwitchEventRaid, TwitchEventReSub, TwitchEventRedeem,
-        TwitchEventShoutoutReceive, TwitchEventSub, TwitchEventUser,
+        TwitchEventGiftSub, TwitchEventHypeTrain, TwitchEventPoll, TwitchEventPrediction,
+        TwitchEventRaid, TwitchEventReSub, TwitchEventRedeem, TwitchEventShoutoutReceive,
+        TwitchEventSub, TwitchEventUser, TwitchManager,
     },
 };
 
+use super::seen_viewers::SeenViewerTracker;
+
+/// Maximum number of compiled patterns kept in the [REGEX_CACHE]
+const MAX_CACHED_PATTERNS: usize = 256;
+
+/// Compiled regular expressions keyed by their pattern and whether they
+/// ignore case, prevents compiling event patterns for every chat message
+static REGEX_CACHE: LazyLock<Mutex<HashMap<(String, bool), Regex>>> =
+    LazyLock::new(Default::default);
+
 /// Data for matched events to trigger
 ///
 /// TODO: Often small lists, use tinyvec instead?
@@ -68,6 +89,9 @@ pub enum EventInputData {
         cost: i64,
         /// User provided message (For redeems that let you provide a message)
         user_input: String,
+        /// ID of the redemption (Not present for test events)
+        #[serde(default)]
+        redemption_id: Option<RedemptionId>,
     },
 
     /// Bits specific data
@@ -128,6 +152,10 @@ pub enum EventInputData {
 
         /// Optional amount of bits cheered (If user cheered bits)
         cheer: Option<usize>,
+
+        /// Capture groups from a matched chat message trigger
+        #[serde(default)]
+        captures: ChatMessageCaptures,
     },
 
     /// Raid specific data
@@ -148,12 +176,139 @@ pub enum EventInputData {
         viewer_count: i64,
     },
 
+    /// Hype train specific data
+    HypeTrain {
+        /// Current level of the hype train
+        level: i64,
+        /// Total points contributed to the hype train
+        total: i64,
+        /// Top contributors to the hype train
+        top_contributions: Vec<HypeTrainContribution>,
+    },
+
+    /// Poll specific data
+    Poll {
+        /// Title of the poll
+        title: String,
+        /// Choices within the poll
+        choices: Vec<PollChoice>,
+        /// Choice with the most votes (Only present when the poll has ended
+        /// without a tie)
+        winning_choice: Option<PollChoice>,
+        /// Choices sharing the most votes, contains more than one choice
+        /// when the poll ended in a tie (Empty until the poll has ended)
+        winning_choices: Vec<PollChoice>,
+    },
+
+    /// Prediction specific data
+    Prediction {
+        /// Title of the prediction
+        title: String,
+        /// Outcomes within the prediction
+        outcomes: Vec<PredictionOutcome>,
+        /// Winning outcome (Only present when the prediction has ended)
+        winning_outcome: Option<PredictionOutcome>,
+        /// Whether the prediction was canceled and refunded instead
+        /// of being resolved
+        canceled: bool,
+    },
+
     /// No additional input data
     #[default]
     #[serde(deserialize_with = "deserialize_ignore_any")]
     None,
 }
 
+/// Contribution towards a hype train
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HypeTrainContribution {
+    /// User who contributed
+    pub user: TwitchEventUser,
+    /// Type of contribution
+    #[serde(rename = "type")]
+    pub ty: ContributionType,
+    /// Total amount contributed (Bits or points for subscriptions)
+    pub total: i64,
+}
+
+/// Choice within a poll
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PollChoice {
+    /// ID of the choice
+    pub id: String,
+    /// Title of the choice
+    pub title: String,
+    /// Total number of votes for the choice
+    pub votes: i64,
+}
+
+/// Outcome within a prediction
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PredictionOutcome {
+    /// ID of the outcome
+    pub id: String,
+    /// Title of the outcome
+    pub title: String,
+    /// Number of users who predicted the outcome
+    pub users: i64,
+    /// Total channel points used on the outcome
+    pub channel_points: i64,
+}
+
+/// Capture groups from a chat message trigger regex pattern
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct ChatMessageCaptures {
+    /// Captured groups by index, the first group is the entire match.
+    /// Groups that did not participate in the match are null
+    pub groups: Vec<Option<String>>,
+    /// Captured groups by name
+    pub named: HashMap<String, String>,
+}
+
+impl EventInputData {
+    /// Get the amount derived from the input data (i.e bits, total gifted
+    /// subs, raiders)
+    pub fn input_amount(&self) -> Option<i64> {
+        match self {
+            EventInputData::Bits { bits, .. } => Some(*bits),
+            EventInputData::GiftedSubscription { total, .. } => Some(*total),
+            EventInputData::Subscription { .. } => Some(1),
+            EventInputData::ReSubscription {
+                cumulative_months, ..
+            } => Some(*cumulative_months),
+            EventInputData::Chat { cheer, .. } => cheer.map(|value| value as i64),
+            EventInputData::Raid { viewers } => Some(*viewers),
+            EventInputData::HypeTrain { level, .. } => Some(*level),
+
+            _ => None,
+        }
+    }
+
+    /// Get the user provided text from the input data (i.e chat
+    /// message, redeem user input)
+    pub fn input_text(&self) -> Option<&str> {
+        match self {
+            EventInputData::Redeem { user_input, .. } => Some(user_input),
+            EventInputData::Bits { message, .. } => Some(message),
+            EventInputData::ReSubscription { message, .. } => Some(message),
+            EventInputData::Chat { message, .. } => Some(message),
+
+            _ => None,
+        }
+    }
+
+    /// Get the subscription tier from the input data
+    pub fn subscription_tier(&self) -> Option<&SubscriptionTier> {
+        match self {
+            EventInputData::Subscription { tier, .. } => Some(tier),
+            EventInputData::GiftedSubscription { tier, .. } => Some(tier),
+            EventInputData::ReSubscription { tier, .. } => Some(tier),
+
+            _ => None,
+        }
+    }
+}
+
 pub fn deserialize_ignore_any<'de, D: Deserializer<'de>, T: Default>(
     deserializer: D,
 ) -> Result<T, D::Error> {
@@ -164,7 +319,7 @@ pub async fn match_redeem_event(
     db: &DatabaseConnection,
     event: TwitchEventRedeem,
 ) -> anyhow::Result<EventMatchingData> {
-    let events = EventModel::get_by_trigger_type(db, EventTriggerType::Redeem).await;
+    let events = EventModel::get_by_trigger_type_with_disabled(db, EventTriggerType::Redeem).await;
     let events = match events {
         Ok(value) => value,
         Err(err) => {
@@ -182,7 +337,7 @@ pub async fn match_redeem_event(
         .filter(|event| {
             matches!(
                 &event.trigger,
-                EventTrigger::Redeem { reward_id } if event_reward_id.eq(reward_id)
+                EventTrigger::Redeem { reward_id, .. } if event_reward_id.eq(reward_id)
             )
         })
         .collect();
@@ -193,6 +348,7 @@ pub async fn match_redeem_event(
             reward_name: event.reward.title.clone(),
             cost: event.reward.cost,
             user_input: event.user_input,
+            redemption_id: Some(event.id),
         },
         user: Some(TwitchEventUser {
             id: event.user_id,
@@ -395,8 +551,23 @@ pub async fn match_re_subscription_event(
 
 pub async fn match_chat_event(
     db: &DatabaseConnection,
+    twitch_manager: &TwitchManager,
+    seen_viewers: &SeenViewerTracker,
     event: TwitchEventChatMsg,
 ) -> anyhow::Result<EventMatchingData> {
+    // Messages sent by the app are not matched so replies containing
+    // a keyword or command cannot trigger themselves
+    if twitch_manager
+        .is_own_chat_message(&event.user_id, &event.message_id)
+        .await
+    {
+        return Ok(EventMatchingData {
+            events: Default::default(),
+            commands: Default::default(),
+            event_data: Default::default(),
+        });
+    }
+
     let message = event.message.text.clone();
     let mut args: Vec<String> = message
         .split_whitespace()
@@ -409,13 +580,40 @@ pub async fn match_chat_event(
         Some(args.remove(0))
     };
 
-    let (events, commands) = if let Some(first_arg) = first_arg {
+    // Load all chat message event triggers
+    let chat_events = match EventModel::get_by_trigger_type(db, EventTriggerType::ChatMessage).await
+    {
+        Ok(value) => value,
+        Err(err) => {
+            error!("failed to load events: {:?}", err);
+            Default::default()
+        }
+    };
+
+    // Filter events for matching chat messages
+    let chat_events = chat_events
+        .into_iter()
+        .filter(|event| match &event.trigger {
+            EventTrigger::ChatMessage {
+                matcher,
+                case_sensitive,
+            } => match get_chat_message_captures(matcher, *case_sensitive, &message) {
+                Ok(captures) => captures.is_some(),
+                Err(err) => {
+                    error!("failed to match chat message event: {:?}", err);
+                    false
+                }
+            },
+            _ => false,
+        });
+
+    let (mut events, commands) = if let Some(first_arg) = first_arg {
         // Get the command argument from the first argument
         let command_arg = first_arg.trim().to_lowercase();
 
         let (events, commands) = join!(
             // Load all command event triggers
-            EventModel::get_by_trigger_type(db, EventTriggerType::Command),
+            EventModel::get_by_trigger_type_with_disabled(db, EventTriggerType::Command),
             // Load all commands
             CommandModel::get_by_command(db, &command_arg),
         );
@@ -437,7 +635,7 @@ pub async fn match_chat_event(
         };
 
         // Filter events for matching command messages
-        let events = events
+        let events: Vec<EventModel> = events
             .into_iter()
             .filter(|event| matches!(&event.trigger, EventTrigger::Command { message } if message.trim().to_lowercase().eq(&command_arg)))
             .collect();
@@ -466,12 +664,42 @@ pub async fn match_chat_event(
         (Default::default(), Default::default())
     };
 
+    events.extend(chat_events);
+
+    let first_message = seen_viewers
+        .record_message(db, &event.user_id, Utc::now())
+        .await;
+
+    // Twitch marks first messages when available, otherwise viewers
+    // new to the app are treated as first messages
+    let first_ever = event.first_message || first_message.first_ever;
+
+    // Load events for the first message triggers
+    let first_message_types = [
+        (first_ever, EventTriggerType::FirstMessage),
+        (
+            first_message.first_stream,
+            EventTriggerType::FirstStreamMessage,
+        ),
+    ];
+
+    for (_, trigger_type) in first_message_types
+        .into_iter()
+        .filter(|(is_first, _)| *is_first)
+    {
+        match EventModel::get_by_trigger_type(db, trigger_type).await {
+            Ok(value) => events.extend(value),
+            Err(err) => error!("failed to load events: {:?}", err),
+        }
+    }
+
     let event_data = EventData {
         input_data: EventInputData::Chat {
             message_id: event.message_id,
             message: event.message.text,
             fragments: event.message.fragments,
             cheer: event.cheer.map(|cheer| cheer.bits),
+            captures: Default::default(),
         },
         user: Some(TwitchEventUser {
             id: event.user_id,
@@ -594,3 +822,544 @@ pub async fn match_shoutout_receive_event(
         event_data,
     })
 }
+
+pub async fn match_hype_train_event(
+    db: &DatabaseConnection,
+    trigger_type: EventTriggerType,
+    event: TwitchEventHypeTrain,
+) -> anyhow::Result<EventMatchingData> {
+    let events = EventModel::get_by_trigger_type(db, trigger_type).await;
+
+    let events = match events {
+        Ok(value) => value,
+        Err(err) => {
+            error!("failed to load events: {:?}", err);
+            Default::default()
+        }
+    };
+
+    let level = event.level;
+
+    // Filter level up events for the matching minimum level
+    let events = events
+        .into_iter()
+        .filter(|event| match &event.trigger {
+            EventTrigger::HypeTrainLevelUp { min_level } => level >= *min_level as i64,
+            _ => true,
+        })
+        .collect();
+
+    let top_contributions = event
+        .top_contributions
+        .into_iter()
+        .map(|contribution| HypeTrainContribution {
+            user: TwitchEventUser {
+                id: contribution.user_id,
+                name: contribution.user_login,
+                display_name: contribution.user_name,
+            },
+            ty: contribution.type_,
+            total: contribution.total,
+        })
+        .collect();
+
+    let event_data = EventData {
+        input_data: EventInputData::HypeTrain {
+            level,
+            total: event.total,
+            top_contributions,
+        },
+        user: None,
+    };
+
+    Ok(EventMatchingData {
+        events,
+        commands: Default::default(),
+        event_data,
+    })
+}
+
+pub async fn match_poll_event(
+    db: &DatabaseConnection,
+    trigger_type: EventTriggerType,
+    event: TwitchEventPoll,
+) -> anyhow::Result<EventMatchingData> {
+    let is_end = matches!(trigger_type, EventTriggerType::PollEnd);
+    let events = EventModel::get_by_trigger_type(db, trigger_type).await;
+
+    let events = match events {
+        Ok(value) => value,
+        Err(err) => {
+            error!("failed to load events: {:?}", err);
+            Default::default()
+        }
+    };
+
+    let choices: Vec<PollChoice> = event
+        .choices
+        .into_iter()
+        .map(|choice| PollChoice {
+            id: choice.id,
+            title: choice.title,
+            votes: choice.votes.unwrap_or_default(),
+        })
+        .collect();
+
+    // Winning choices are only known once the poll has ended
+    let winning_choices = if is_end {
+        get_poll_winning_choices(&choices)
+    } else {
+        Vec::new()
+    };
+
+    // Ties don't have a single winner
+    let winning_choice = match winning_choices.as_slice() {
+        [winning_choice] => Some(winning_choice.clone()),
+        _ => None,
+    };
+
+    let event_data = EventData {
+        input_data: EventInputData::Poll {
+            title: event.title,
+            choices,
+            winning_choice,
+            winning_choices,
+        },
+        user: None,
+    };
+
+    Ok(EventMatchingData {
+        events,
+        commands: Default::default(),
+        event_data,
+    })
+}
+
+/// Get the choices sharing the most votes within a poll
+fn get_poll_winning_choices(choices: &[PollChoice]) -> Vec<PollChoice> {
+    let max_votes = match choices.iter().map(|choice| choice.votes).max() {
+        Some(value) => value,
+        None => return Vec::new(),
+    };
+
+    choices
+        .iter()
+        .filter(|choice| choice.votes == max_votes)
+        .cloned()
+        .collect()
+}
+
+pub async fn match_prediction_event(
+    db: &DatabaseConnection,
+    trigger_type: EventTriggerType,
+    event: TwitchEventPrediction,
+) -> anyhow::Result<EventMatchingData> {
+    let events = EventModel::get_by_trigger_type(db, trigger_type).await;
+
+    let events = match events {
+        Ok(value) => value,
+        Err(err) => {
+            error!("failed to load events: {:?}", err);
+            Default::default()
+        }
+    };
+
+    let outcomes: Vec<PredictionOutcome> = event
+        .outcomes
+        .into_iter()
+        .map(|outcome| PredictionOutcome {
+            id: outcome.id,
+            title: outcome.title,
+            users: outcome.users.unwrap_or_default(),
+            channel_points: outcome.channel_points.unwrap_or_default(),
+        })
+        .collect();
+
+    let winning_outcome = event.winning_outcome_id.and_then(|winning_outcome_id| {
+        outcomes
+            .iter()
+            .find(|outcome| outcome.id == winning_outcome_id)
+            .cloned()
+    });
+
+    let event_data = EventData {
+        input_data: EventInputData::Prediction {
+            title: event.title,
+            outcomes,
+            winning_outcome,
+            canceled: event.canceled,
+        },
+        user: None,
+    };
+
+    Ok(EventMatchingData {
+        events,
+        commands: Default::default(),
+        event_data,
+    })
+}
+
+pub async fn match_stream_event(
+    db: &DatabaseConnection,
+    trigger_type: EventTriggerType,
+) -> anyhow::Result<EventMatchingData> {
+    let events = EventModel::get_by_trigger_type(db, trigger_type).await;
+
+    let events = match events {
+        Ok(value) => value,
+        Err(err) => {
+            error!("failed to load events: {:?}", err);
+            Default::default()
+        }
+    };
+
+    Ok(EventMatchingData {
+        events,
+        commands: Default::default(),
+        event_data: EventData {
+            user: None,
+            input_data: EventInputData::None,
+        },
+    })
+}
+
+/// Checks that the provided `input_data` meets all the event `conditions`
+pub fn is_event_conditions_met(
+    conditions: &EventConditions,
+    input_data: &EventInputData,
+) -> anyhow::Result<bool> {
+    for condition in &conditions.0 {
+        if !is_event_condition_met(condition, input_data)? {
+            return Ok(false);
+        }
+    }
+
+    Ok(true)
+}
+
+/// Checks if the provided `input_data` meets a specific `condition`
+pub fn is_event_condition_met(
+    condition: &EventCondition,
+    input_data: &EventInputData,
+) -> anyhow::Result<bool> {
+    match condition {
+        EventCondition::InputAmount { min, max } => {
+            let amount = match input_data.input_amount() {
+                Some(value) => value,
+                None => return Ok(false),
+            };
+
+            Ok(min.is_none_or(|min| amount >= min) && max.is_none_or(|max| amount <= max))
+        }
+        EventCondition::SubscriptionTier { tier } => Ok(input_data
+            .subscription_tier()
+            .is_some_and(|value| value.eq(tier))),
+        EventCondition::InputTextContains { value, ignore_case } => {
+            let text = match input_data.input_text() {
+                Some(value) => value,
+                None => return Ok(false),
+            };
+
+            if *ignore_case {
+                Ok(text.to_lowercase().contains(&value.to_lowercase()))
+            } else {
+                Ok(text.contains(value.as_str()))
+            }
+        }
+        EventCondition::InputTextMatches {
+            pattern,
+            ignore_case,
+        } => {
+            let text = match input_data.input_text() {
+                Some(value) => value,
+                None => return Ok(false),
+            };
+
+            let regex =
+                get_cached_regex(pattern, *ignore_case).context("invalid condition pattern")?;
+
+            Ok(regex.is_match(text))
+        }
+    }
+}
+
+/// Get the compiled regular expression for the `pattern` from the cache,
+/// compiling and storing it if it has not been compiled yet
+fn get_cached_regex(pattern: &str, case_insensitive: bool) -> Result<Regex, regex::Error> {
+    let key = (pattern.to_string(), case_insensitive);
+
+    if let Some(regex) = REGEX_CACHE
+        .lock()
+        .expect("regex cache lock poisoned")
+        .get(&key)
+    {
+        return Ok(regex.clone());
+    }
+
+    let regex = RegexBuilder::new(pattern)
+        .case_insensitive(case_insensitive)
+        .build()?;
+
+    let cache = &mut *REGEX_CACHE.lock().expect("regex cache lock poisoned");
+
+    // Patterns from edited or deleted events are never used again,
+    // clear the cache rather than letting it grow forever
+    if cache.len() >= MAX_CACHED_PATTERNS {
+        cache.clear();
+    }
+
+    cache.insert(key, regex.clone());
+
+    Ok(regex)
+}
+
+/// Matches the chat `message` against the provided `matcher` producing the
+/// capture groups when the message matches
+pub fn get_chat_message_captures(
+    matcher: &ChatMessageMatcher,
+    case_sensitive: bool,
+    message: &str,
+) -> anyhow::Result<Option<ChatMessageCaptures>> {
+    match matcher {
+        ChatMessageMatcher::Keyword { keyword } => {
+            let is_match = if case_sensitive {
+                message.contains(keyword.as_str())
+            } else {
+                message.to_lowercase().contains(&keyword.to_lowercase())
+            };
+
+            Ok(is_match.then(ChatMessageCaptures::default))
+        }
+        ChatMessageMatcher::Regex { pattern } => {
+            let regex = get_cached_regex(pattern, !case_sensitive)
+                .context("invalid chat message pattern")?;
+
+            let captures = match regex.captures(message) {
+                Some(value) => value,
+                None => return Ok(None),
+            };
+
+            let groups = captures
+                .iter()
+                .map(|group| group.map(|group| group.as_str().to_string()))
+                .collect();
+
+            let named = regex
+                .capture_names()
+                .flatten()
+                .filter_map(|name| {
+                    captures
+                        .name(name)
+                        .map(|group| (name.to_string(), group.as_str().to_string()))
+                })
+                .collect();
+
+            Ok(Some(ChatMessageCaptures { groups, named }))
+        }
+    }
+}
+
+/// Provides the chat message capture groups for the `trigger` to the
+/// event `input_data`, does nothing for non chat message triggers
+pub fn apply_chat_message_captures(trigger: &EventTrigger, input_data: &mut EventInputData) {
+    let (matcher, case_sensitive) = match trigger {
+        EventTrigger::ChatMessage {
+            matcher,
+            case_sensitive,
+        } => (matcher, *case_sensitive),
+        _ => return,
+    };
+
+    if let EventInputData::Chat {
+        message, captures, ..
+    } = input_data
+    {
+        if let Ok(Some(value)) = get_chat_message_captures(matcher, case_sensitive, message) {
+            *captures = value;
+        }
+    }
+}
+
+/// Validates the chat message trigger, ensures any regular expression
+/// patterns are valid
+pub fn validate_event_trigger(trigger: &EventTrigger) -> anyhow::Result<()> {
+    if let EventTrigger::ChatMessage {
+        matcher: ChatMessageMatcher::Regex { pattern },
+        ..
+    } = trigger
+    {
+        RegexBuilder::new(pattern)
+            .build()
+            .with_context(|| format!("invalid chat message pattern: {pattern}"))?;
+    }
+
+    Ok(())
+}
+
+/// Validates the provided event conditions, ensures any regular
+/// expression patterns are valid
+pub fn validate_event_conditions(conditions: &EventConditions) -> anyhow::Result<()> {
+    for condition in &conditions.0 {
+        if let EventCondition::InputTextMatches { pattern, .. } = condition {
+            RegexBuilder::new(pattern)
+                .build()
+                .with_context(|| format!("invalid condition pattern: {pattern}"))?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use twitch_api::types::SubscriptionTier;
+
+    use super::{
+        get_cached_regex, get_chat_message_captures, get_poll_winning_choices,
+        is_event_condition_met, EventInputData, PollChoice, REGEX_CACHE,
+    };
+    use crate::database::entity::events::{ChatMessageMatcher, EventCondition};
+
+    fn bits_input(bits: i64, message: &str) -> EventInputData {
+        EventInputData::Bits {
+            bits,
+            anonymous: false,
+            message: message.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_input_amount_condition() {
+        let condition = EventCondition::InputAmount {
+            min: Some(100),
+            max: Some(500),
+        };
+
+        assert!(!is_event_condition_met(&condition, &bits_input(99, "")).unwrap());
+        assert!(is_event_condition_met(&condition, &bits_input(100, "")).unwrap());
+        assert!(is_event_condition_met(&condition, &bits_input(500, "")).unwrap());
+        assert!(!is_event_condition_met(&condition, &bits_input(501, "")).unwrap());
+        assert!(!is_event_condition_met(&condition, &EventInputData::None).unwrap());
+    }
+
+    #[test]
+    fn test_subscription_tier_condition() {
+        let condition = EventCondition::SubscriptionTier {
+            tier: SubscriptionTier::Tier3,
+        };
+
+        let tier_3 = EventInputData::Subscription {
+            tier: SubscriptionTier::Tier3,
+            is_gift: false,
+        };
+        let tier_1 = EventInputData::Subscription {
+            tier: SubscriptionTier::Tier1,
+            is_gift: false,
+        };
+
+        assert!(is_event_condition_met(&condition, &tier_3).unwrap());
+        assert!(!is_event_condition_met(&condition, &tier_1).unwrap());
+    }
+
+    #[test]
+    fn test_input_text_conditions() {
+        let contains = EventCondition::InputTextContains {
+            value: "hello".to_string(),
+            ignore_case: true,
+        };
+        let matches = EventCondition::InputTextMatches {
+            pattern: "^bonk( \\d+)?$".to_string(),
+            ignore_case: false,
+        };
+
+        assert!(is_event_condition_met(&contains, &bits_input(1, "Well HELLO there")).unwrap());
+        assert!(!is_event_condition_met(&contains, &bits_input(1, "goodbye")).unwrap());
+        assert!(is_event_condition_met(&matches, &bits_input(1, "bonk 5")).unwrap());
+        assert!(!is_event_condition_met(&matches, &bits_input(1, "BONK")).unwrap());
+    }
+
+    #[test]
+    fn test_chat_message_keyword() {
+        let matcher = ChatMessageMatcher::Keyword {
+            keyword: "Hello".to_string(),
+        };
+
+        assert!(get_chat_message_captures(&matcher, false, "oh hello there")
+            .unwrap()
+            .is_some());
+        assert!(get_chat_message_captures(&matcher, true, "oh hello there")
+            .unwrap()
+            .is_none());
+        assert!(get_chat_message_captures(&matcher, true, "oh Hello there")
+            .unwrap()
+            .is_some());
+    }
+
+    #[test]
+    fn test_cached_regex() {
+        let pattern = "^cached (\\d+)$";
+
+        let regex = get_cached_regex(pattern, true).unwrap();
+        assert!(regex.is_match("CACHED 10"));
+        assert!(REGEX_CACHE
+            .lock()
+            .unwrap()
+            .contains_key(&(pattern.to_string(), true)));
+
+        // Case sensitivity is part of the cache key
+        let regex = get_cached_regex(pattern, false).unwrap();
+        assert!(!regex.is_match("CACHED 10"));
+
+        assert!(get_cached_regex("(unclosed", false).is_err());
+    }
+
+    #[test]
+    fn test_chat_message_regex_captures() {
+        let matcher = ChatMessageMatcher::Regex {
+            pattern: "bonk (?<target>\\w+)( \\d+)?".to_string(),
+        };
+
+        let captures = get_chat_message_captures(&matcher, false, "please BONK jacob")
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(
+            captures.groups,
+            vec![
+                Some("BONK jacob".to_string()),
+                Some("jacob".to_string()),
+                None
+            ]
+        );
+        assert_eq!(
+            captures.named.get("target").map(String::as_str),
+            Some("jacob")
+        );
+
+        assert!(
+            get_chat_message_captures(&matcher, true, "please BONK jacob")
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_poll_winning_choices() {
+        let choice = |id: &str, votes: i64| PollChoice {
+            id: id.to_string(),
+            title: id.to_string(),
+            votes,
+        };
+
+        let winners = get_poll_winning_choices(&[choice("a", 3), choice("b", 5)]);
+        assert_eq!(winners.len(), 1);
+        assert_eq!(winners[0].id, "b");
+
+        // Ties expose every choice sharing the most votes
+        let winners = get_poll_winning_choices(&[choice("a", 5), choice("b", 5), choice("c", 1)]);
+        let ids: Vec<&str> = winners.iter().map(|choice| choice.id.as_str()).collect();
+        assert_eq!(ids, ["a", "b"]);
+
+        assert!(get_poll_winning_choices(&[]).is_empty());
+    }
+}
@@ -1,7 +1,15 @@
+pub mod chat_activity;
+pub mod cooldowns;
+pub mod feedback;
+pub mod group_cache;
 pub mod matching;
 pub mod outcome;
+pub mod pause;
 pub mod processing;
+pub mod queue;
 pub mod scheduler;
+pub mod seen_viewers;
+pub mod template;
 
 use serde::{Deserialize, Serialize};
 use tokio::sync::broadcast;
@@ -79,6 +87,9 @@ pub enum EventMessage {
     AppDataUpdated {
         app_data: Box<AppData>,
     },
+
+    /// Stop any in progress throws and sounds
+    FlushOutcomes,
 }
 
 pub struct EventRecvHandle(pub broadcast::Receiver<EventMessage>);
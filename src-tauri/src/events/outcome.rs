@@ -1,57 +1,291 @@
-use std::{collections::HashSet, sync::Arc};
+use std::{collections::HashSet, fmt, sync::Arc, time::Duration};
 
-use anyhow::{anyhow, Context};
+use anyhow::{anyhow, bail, Context};
 use chrono::Utc;
+use futures::future::BoxFuture;
+use log::error;
+use rand::distributions::{Distribution, WeightedIndex};
 use sea_orm::DatabaseConnection;
+use serde::Serialize;
+use tokio::sync::broadcast;
 use uuid::Uuid;
 
 use crate::{
     database::entity::{
         events::{
             EventOutcome, EventOutcomeBits, EventOutcomeChannelEmotes, EventOutcomePlaySound,
-            EventOutcomeScript, EventOutcomeSendChat, EventOutcomeThrowable,
-            EventOutcomeTriggerHotkey, ThrowableAmountData,
+            EventOutcomeRandom, EventOutcomeScript, EventOutcomeSendChat, EventOutcomeSequence,
+            EventOutcomeThrowable, EventOutcomeTriggerHotkey, ThrowableAmountData,
         },
         items::ThrowableImageConfig,
-        EventModel, ItemModel, SoundModel,
+        ItemModel, SoundModel,
     },
     script::runtime::{RuntimeExecutionContext, ScriptExecutorHandle},
     state::app_data::{ItemWithImpactSoundIds, ItemsWithSounds},
-    twitch::manager::TwitchManager,
+    twitch::{chat_queue::ChatPriority, manager::TwitchManager},
 };
 
 use super::{
     matching::{EventData, EventInputData},
+    template::{render_template, validate_template, TemplateContext, TemplateVariables},
     EventMessage, ThrowItemConfig, ThrowItemMessage,
 };
 
-/// Produce a message for an outcome
-pub async fn produce_outcome_message(
-    db: &DatabaseConnection,
-    twitch_manager: &Arc<TwitchManager>,
-    script_handle: &ScriptExecutorHandle,
+/// Shared state required to produce outcomes
+#[derive(Clone, Copy)]
+pub struct OutcomeContext<'a> {
+    pub db: &'a DatabaseConnection,
+    pub twitch_manager: &'a Arc<TwitchManager>,
+    pub script_handle: &'a ScriptExecutorHandle,
+    pub event_sender: &'a broadcast::Sender<EventMessage>,
+    /// Priority of any chat messages sent by the outcome
+    pub chat_priority: ChatPriority,
+}
 
-    event: EventModel,
+/// Produce and send the message(s) for an outcome
+///
+/// Returns optional metadata about the produced outcome that should be
+/// stored alongside the execution (i.e the steps of a sequence)
+pub fn produce_outcome_message(
+    ctx: OutcomeContext<'_>,
+    event_id: Uuid,
+    outcome: EventOutcome,
     event_data: EventData,
-) -> anyhow::Result<Option<EventMessage>> {
-    match event.outcome {
-        EventOutcome::ThrowBits(data) => throw_bits_outcome(db, event_data, data).await.map(Some),
-        EventOutcome::Throwable(data) => throwable_outcome(db, event_data, data).await.map(Some),
-        EventOutcome::TriggerHotkey(data) => trigger_hotkey_outcome(data).map(Some),
-        EventOutcome::PlaySound(data) => play_sound_outcome(db, data).await.map(Some),
+) -> BoxFuture<'_, anyhow::Result<Option<serde_json::Value>>> {
+    Box::pin(async move {
+        let OutcomeContext {
+            db,
+            twitch_manager,
+            script_handle,
+            event_sender,
+            chat_priority,
+        } = ctx;
+
+        let msg = match outcome {
+            EventOutcome::ThrowBits(data) => throw_bits_outcome(db, event_data, data).await?,
+            EventOutcome::Throwable(data) => throwable_outcome(db, event_data, data).await?,
+            EventOutcome::TriggerHotkey(data) => trigger_hotkey_outcome(data)?,
+            EventOutcome::PlaySound(data) => play_sound_outcome(db, data).await?,
+            EventOutcome::SendChatMessage(data) => {
+                send_chat_message(db, twitch_manager, chat_priority, event_data, data).await?;
+                return Ok(None);
+            }
+            EventOutcome::Script(data) => {
+                execute_script(script_handle, event_id, event_data, data).await?;
+                return Ok(None);
+            }
+            EventOutcome::ChannelEmotes(data) => {
+                throw_channel_emotes_outcome(twitch_manager, event_data, data).await?
+            }
+            EventOutcome::Sequence(data) => {
+                return sequence_outcome(ctx, event_id, event_data, data)
+                    .await
+                    .map(Some)
+            }
+            EventOutcome::Random(data) => {
+                return random_outcome(ctx, event_id, event_data, data)
+                    .await
+                    .map(Some)
+            }
+        };
+
+        _ = event_sender.send(msg);
+
+        Ok(None)
+    })
+}
+
+/// Error for an outcome that failed after partially running, carries the
+/// metadata for the parts that did run so it can still be stored
+#[derive(Debug)]
+pub struct PartialOutcomeError {
+    /// Metadata for the parts of the outcome that ran
+    pub metadata: serde_json::Value,
+    /// Description of the failure
+    message: String,
+}
+
+impl fmt::Display for PartialOutcomeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for PartialOutcomeError {}
+
+/// Get the metadata that should be stored for the result of an outcome,
+/// outcomes that failed after partially running still provide metadata
+pub fn get_outcome_metadata(
+    result: &anyhow::Result<Option<serde_json::Value>>,
+) -> Option<serde_json::Value> {
+    match result {
+        Ok(metadata) => metadata.clone(),
+        Err(err) => err
+            .downcast_ref::<PartialOutcomeError>()
+            .map(|err| err.metadata.clone()),
+    }
+}
+
+/// Validates the provided outcome, ensures any chat message templates
+/// only use known variables and random outcomes have a choice that
+/// can be picked
+pub fn validate_event_outcome(outcome: &EventOutcome) -> anyhow::Result<()> {
+    match outcome {
         EventOutcome::SendChatMessage(data) => {
-            send_chat_message(twitch_manager, event_data, data).await?;
-            Ok(None)
+            validate_template(&data.template, TemplateVariables::Message)
+                .context("invalid chat message template")
         }
-        EventOutcome::Script(data) => {
-            execute_script(script_handle, event.id, event_data, data).await?;
-            Ok(None)
+        EventOutcome::Sequence(data) => data
+            .steps
+            .iter()
+            .try_for_each(|step| validate_event_outcome(&step.outcome)),
+        EventOutcome::Random(data) => {
+            if data.choices.is_empty() {
+                bail!("random outcome must have at least one choice");
+            }
+
+            if data.choices.iter().all(|choice| choice.weight == 0) {
+                bail!("random outcome must have a choice with a weight above zero");
+            }
+
+            data.choices
+                .iter()
+                .try_for_each(|choice| validate_event_outcome(&choice.outcome))
         }
-        EventOutcome::ChannelEmotes(data) => {
-            throw_channel_emotes_outcome(twitch_manager, event_data, data)
-                .await
-                .map(Some)
+        _ => Ok(()),
+    }
+}
+
+/// Metadata stored for each step of a sequence outcome
+#[derive(Debug, Serialize)]
+struct SequenceStepMetadata {
+    /// Index of the step within the sequence
+    step: usize,
+    /// Delay before the step was run
+    delay: u32,
+    /// Metadata produced by the step outcome
+    outcome: Option<serde_json::Value>,
+    /// Error message if the step failed
+    error: Option<String>,
+}
+
+/// Run each step of a sequence outcome in order, waiting for the
+/// delay of each step before running it.
+///
+/// Failing steps are logged but do not stop the remaining steps from
+/// running, the sequence fails once all the steps have run if any of the
+/// steps failed. The metadata for every step is kept on failure
+/// through [PartialOutcomeError]
+async fn sequence_outcome(
+    ctx: OutcomeContext<'_>,
+    event_id: Uuid,
+    event_data: EventData,
+    data: EventOutcomeSequence,
+) -> anyhow::Result<serde_json::Value> {
+    let mut steps = Vec::with_capacity(data.steps.len());
+
+    for (index, step) in data.steps.into_iter().enumerate() {
+        // Wait for the step delay
+        tokio::time::sleep(Duration::from_millis(step.delay as u64)).await;
+
+        let result = produce_outcome_message(ctx, event_id, step.outcome, event_data.clone()).await;
+
+        let outcome = get_outcome_metadata(&result);
+        let error = match result {
+            Ok(_) => None,
+            Err(err) => {
+                error!("error while executing sequence step {index}: {err:?}");
+                Some(err.to_string())
+            }
+        };
+
+        steps.push(SequenceStepMetadata {
+            step: index,
+            delay: step.delay,
+            outcome,
+            error,
+        });
+    }
+
+    create_sequence_metadata(steps)
+}
+
+/// Create the metadata for the steps of a sequence, fails with the metadata
+/// when any of the steps failed
+fn create_sequence_metadata(steps: Vec<SequenceStepMetadata>) -> anyhow::Result<serde_json::Value> {
+    let failed = steps.iter().filter(|step| step.error.is_some()).count();
+    let total = steps.len();
+    let metadata = serde_json::json!({ "steps": steps });
+
+    if failed > 0 {
+        return Err(PartialOutcomeError {
+            metadata,
+            message: format!("{failed} of {total} sequence steps failed"),
         }
+        .into());
+    }
+
+    Ok(metadata)
+}
+
+/// Metadata stored for the chosen random outcome
+#[derive(Debug, Serialize)]
+struct RandomChoiceMetadata {
+    /// Index of the chosen choice
+    choice: usize,
+    /// Weight of the chosen choice
+    weight: u32,
+    /// Total weight of all the choices
+    total_weight: u32,
+    /// Copy of the outcome that was chosen
+    chosen: EventOutcome,
+    /// Metadata produced by the chosen outcome
+    outcome: Option<serde_json::Value>,
+}
+
+/// Choose one of the outcomes from a random outcome based on the
+/// weights of each choice and run it, the chosen outcome is kept in
+/// the metadata even when it fails
+async fn random_outcome(
+    ctx: OutcomeContext<'_>,
+    event_id: Uuid,
+    event_data: EventData,
+    data: EventOutcomeRandom,
+) -> anyhow::Result<serde_json::Value> {
+    let choice = {
+        let weights = data.choices.iter().map(|choice| choice.weight);
+        let distribution = WeightedIndex::new(weights)
+            .map_err(|err| anyhow!("random outcome choices are invalid: {err}"))?;
+        distribution.sample(&mut rand::thread_rng())
+    };
+
+    let total_weight: u32 = data.choices.iter().map(|choice| choice.weight).sum();
+    let chosen = data
+        .choices
+        .into_iter()
+        .nth(choice)
+        .context("chosen random outcome is missing")?;
+
+    let result = produce_outcome_message(ctx, event_id, chosen.outcome.clone(), event_data).await;
+
+    let metadata = RandomChoiceMetadata {
+        choice,
+        weight: chosen.weight,
+        total_weight,
+        chosen: chosen.outcome,
+        outcome: get_outcome_metadata(&result),
+    };
+
+    let metadata =
+        serde_json::to_value(metadata).context("failed to serialize random outcome metadata")?;
+
+    match result {
+        Ok(_) => Ok(metadata),
+        Err(err) => Err(PartialOutcomeError {
+            metadata,
+            message: err.to_string(),
+        }
+        .into()),
     }
 }
 
@@ -73,58 +307,22 @@ pub async fn execute_script(
 }
 
 async fn send_chat_message(
+    db: &DatabaseConnection,
     twitch_manager: &Arc<TwitchManager>,
+    chat_priority: ChatPriority,
     event_data: EventData,
     data: EventOutcomeSendChat,
 ) -> anyhow::Result<()> {
-    let mut message = data.template;
-
-    let user_name = event_data
-        .user
-        .map(|user| user.name.to_string())
-        .unwrap_or_else(|| "Anonymous".to_string());
-
-    message = message.replace("$(user)", user_name.as_str());
-
-    match event_data.input_data {
-        EventInputData::Redeem {
-            reward_name,
-            cost,
-            user_input,
-            ..
-        } => {
-            message = message.replace("$(userInput)", user_input.as_str());
-            message = message.replace("$(rewardName)", reward_name.as_str());
-            message = message.replace("$(rewardCost)", cost.to_string().as_str());
-        }
-        EventInputData::Bits {
-            bits,
-            message: user_input,
-            ..
-        } => {
-            message = message.replace("$(userInput)", user_input.to_string().as_str());
-            message = message.replace("$(bits)", bits.to_string().as_str());
-        }
-        EventInputData::AdBreakBegin { duration_seconds } => {
-            message = message.replace("$(duration)", duration_seconds.to_string().as_str());
-        }
-        _ => {}
-    }
-
-    if message.len() < 500 {
-        twitch_manager.send_chat_message(&message).await?;
-    } else {
-        let mut chars = message.chars();
-
-        loop {
-            let message = chars.by_ref().take(500).collect::<String>();
-            if message.is_empty() {
-                break;
-            }
+    let ctx = TemplateContext {
+        user: event_data.user.as_ref(),
+        input_data: Some(&event_data.input_data),
+        ..Default::default()
+    };
+    let message = render_template(db, &data.template, &ctx).await;
 
-            twitch_manager.send_chat_message(&message).await?;
-        }
-    }
+    twitch_manager
+        .send_chat_message_with_priority(&message, chat_priority)
+        .await?;
 
     Ok(())
 }
@@ -228,22 +426,6 @@ async fn throw_channel_emotes_outcome(
     create_throwable_message(items, data.amount, None)
 }
 
-fn get_event_data_input_amount(event_data: &EventData) -> Option<i64> {
-    // Compute amount derived from input
-    match &event_data.input_data {
-        EventInputData::Bits { bits, .. } => Some(*bits),
-        EventInputData::GiftedSubscription { total, .. } => Some(*total),
-        EventInputData::Subscription { .. } => Some(1),
-        EventInputData::ReSubscription {
-            cumulative_months, ..
-        } => Some(*cumulative_months),
-        EventInputData::Chat { cheer, .. } => cheer.map(|value| value as i64),
-        EventInputData::Raid { viewers } => Some(*viewers),
-
-        _ => None,
-    }
-}
-
 fn create_throwable_message(
     items: ItemsWithSounds,
     amount: ThrowableAmountData,
@@ -315,7 +497,7 @@ async fn throwable_outcome(
 ) -> anyhow::Result<EventMessage> {
     let items = resolve_items(db, &data.throwable_ids).await?;
 
-    create_throwable_message(items, data.data, get_event_data_input_amount(&event_data))
+    create_throwable_message(items, data.data, event_data.input_data.input_amount())
 }
 
 /// Produce a hotkey trigger message
@@ -448,3 +630,66 @@ pub fn create_default_bit_throwable(amount: i64) -> ItemsWithSounds {
         impact_sounds,
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::database::entity::events::{
+        EventOutcome, EventOutcomeRandom, EventOutcomeRandomChoice, EventOutcomeTriggerHotkey,
+    };
+
+    use super::{
+        create_sequence_metadata, get_outcome_metadata, validate_event_outcome,
+        SequenceStepMetadata,
+    };
+
+    fn random_outcome(weights: &[u32]) -> EventOutcome {
+        EventOutcome::Random(EventOutcomeRandom {
+            choices: weights
+                .iter()
+                .map(|weight| EventOutcomeRandomChoice {
+                    weight: *weight,
+                    outcome: EventOutcome::TriggerHotkey(EventOutcomeTriggerHotkey {
+                        hotkey_id: "hotkey".to_string(),
+                    }),
+                })
+                .collect(),
+        })
+    }
+
+    #[test]
+    fn test_validate_random_weights() {
+        assert!(validate_event_outcome(&random_outcome(&[0, 1])).is_ok());
+        assert!(validate_event_outcome(&random_outcome(&[0, 0])).is_err());
+        assert!(validate_event_outcome(&random_outcome(&[])).is_err());
+    }
+
+    #[test]
+    fn test_partial_sequence_metadata() {
+        let step = |step: usize, error: Option<&str>| SequenceStepMetadata {
+            step,
+            delay: 0,
+            outcome: None,
+            error: error.map(|error| error.to_string()),
+        };
+
+        let result = create_sequence_metadata(vec![
+            step(0, None),
+            step(1, Some("failed to play sound")),
+            step(2, None),
+        ])
+        .map(Some);
+
+        assert_eq!(
+            result.as_ref().unwrap_err().to_string(),
+            "1 of 3 sequence steps failed"
+        );
+
+        // Every step is still available to be stored
+        let metadata = get_outcome_metadata(&result).unwrap();
+        let steps = metadata["steps"].as_array().unwrap();
+        assert_eq!(steps.len(), 3);
+        assert!(steps[0]["error"].is_null());
+        assert_eq!(steps[1]["error"], "failed to play sound");
+        assert!(steps[2]["error"].is_null());
+    }
+}
@@ -0,0 +1,95 @@
+//! # Pause
+//!
+//! Emergency pause that stops events, commands and scheduled events from
+//! producing any outcomes until resumed
+
+use log::error;
+use tokio::sync::broadcast;
+
+use crate::{
+    constants::{PAUSE_CHAT_COMMAND, RESUME_CHAT_COMMAND},
+    database::entity::shared::MinimumRequireRole,
+    state::{app_data::AppDataStore, runtime_app_data::RuntimeAppDataStore},
+    twitch::manager::TwitchEventChatMsg,
+};
+
+use super::{
+    processing::{has_required_role, EventProcessingContext},
+    queue::OutcomeQueue,
+    EventMessage,
+};
+
+/// Sets whether outcomes are paused, when `flush` is set any outcomes waiting
+/// in the queue are dropped and the overlay stops any in progress outcomes
+pub async fn set_outcomes_paused(
+    app_data: &AppDataStore,
+    runtime_app_data: &RuntimeAppDataStore,
+    event_sender: &broadcast::Sender<EventMessage>,
+    outcome_queue: &OutcomeQueue,
+    paused: bool,
+    flush: bool,
+) -> anyhow::Result<()> {
+    app_data.write(|app_data| app_data.paused = paused).await?;
+    runtime_app_data
+        .write(|runtime_app_data| runtime_app_data.paused = paused)
+        .await;
+
+    if flush {
+        outcome_queue.flush();
+        _ = event_sender.send(EventMessage::FlushOutcomes);
+    }
+
+    Ok(())
+}
+
+/// Handles the built in pause and resume chat commands, returns whether
+/// the message was one of the commands
+///
+/// `!vtftk-pause flush` also flushes any queued outcomes
+pub async fn handle_pause_chat_command(
+    ctx: &EventProcessingContext,
+    event: &TwitchEventChatMsg,
+) -> anyhow::Result<bool> {
+    let mut parts = event.message.text.split_whitespace();
+
+    let paused = match parts.next() {
+        Some(PAUSE_CHAT_COMMAND) => true,
+        Some(RESUME_CHAT_COMMAND) => false,
+        _ => return Ok(false),
+    };
+
+    let flush = parts.next().is_some_and(|value| value == "flush");
+
+    // Only moderators and the broadcaster can pause outcomes
+    if !has_required_role(
+        &ctx.twitch_manager,
+        Some(event.user_id.clone()),
+        &MinimumRequireRole::Mod,
+    )
+    .await
+    {
+        return Ok(true);
+    }
+
+    set_outcomes_paused(
+        &ctx.app_data,
+        &ctx.runtime_app_data,
+        &ctx.event_sender,
+        &ctx.outcome_queue,
+        paused,
+        flush,
+    )
+    .await?;
+
+    let message = if paused {
+        "Paused all outcomes"
+    } else {
+        "Resumed all outcomes"
+    };
+
+    if let Err(err) = ctx.twitch_manager.send_chat_message(message).await {
+        error!("failed to send pause reply: {err:?}");
+    }
+
+    Ok(true)
+}
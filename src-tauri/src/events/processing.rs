@@ -1,12 +1,18 @@
 use std::{sync::Arc, time::Duration};
 
 use anyhow::{anyhow, Context};
-use chrono::TimeDelta;
 use futures::{future::BoxFuture, stream::FuturesUnordered};
 use log::{debug, error};
 use sea_orm::{prelude::DateTimeUtc, sqlx::types::chrono::Utc, DatabaseConnection};
-use tokio::{sync::broadcast, try_join};
-use twitch_api::types::UserId;
+use tokio::{
+    join,
+    sync::{broadcast, oneshot},
+    try_join,
+};
+use twitch_api::{
+    helix::points::CustomRewardRedemptionStatus,
+    types::{RedemptionId, SubscriptionTier, UserId},
+};
 
 use crate::{
     database::entity::{
@@ -15,47 +21,72 @@ use crate::{
         },
         commands::CommandOutcome,
         event_executions::{CreateEventExecution, EventExecutionMetadata, EventExecutionModel},
+        events::{EventCooldown, EventTrigger, EventTriggerType},
         shared::MinimumRequireRole,
         CommandModel, EventModel,
     },
-    events::matching::{match_ad_break_event, match_raid_event, match_shoutout_receive_event},
+    events::matching::{
+        match_ad_break_event, match_hype_train_event, match_poll_event, match_prediction_event,
+        match_raid_event, match_shoutout_receive_event, match_stream_event,
+    },
     script::runtime::{
         CommandContext, CommandContextUser, RuntimeExecutionContext, ScriptExecutorHandle,
     },
-    twitch::manager::{TwitchEvent, TwitchEventUser, TwitchManager},
+    state::{app_data::AppDataStore, runtime_app_data::RuntimeAppDataStore},
+    twitch::{
+        chat_queue::ChatPriority,
+        manager::{TwitchEvent, TwitchEventUser, TwitchManager},
+    },
 };
 
 use super::{
+    chat_activity::ChatActivityTracker,
+    cooldowns::{CooldownCharge, CooldownTarget, CooldownTracker},
+    feedback::{send_feedback_reply, FeedbackLimiter, FeedbackReason},
+    group_cache::GroupCache,
     matching::{
-        match_chat_event, match_cheer_bits_event, match_follow_event,
-        match_gifted_subscription_event, match_re_subscription_event, match_redeem_event,
-        match_subscription_event, CommandWithContext, EventData, EventInputData, EventMatchingData,
+        apply_chat_message_captures, is_event_conditions_met, match_chat_event,
+        match_cheer_bits_event, match_follow_event, match_gifted_subscription_event,
+        match_re_subscription_event, match_redeem_event, match_subscription_event,
+        CommandWithContext, EventData, EventInputData, EventMatchingData,
     },
-    outcome::produce_outcome_message,
+    outcome::{get_outcome_metadata, produce_outcome_message, OutcomeContext},
+    pause::handle_pause_chat_command,
+    queue::{OutcomeLane, OutcomeQueue},
+    seen_viewers::SeenViewerTracker,
+    template::{render_template, TemplateContext},
     EventMessage,
 };
 
-pub async fn process_twitch_events(
-    db: DatabaseConnection,
-    twitch_manager: Arc<TwitchManager>,
-    script_handle: ScriptExecutorHandle,
-    event_sender: broadcast::Sender<EventMessage>,
+/// Shared state required for processing and executing events
+#[derive(Clone)]
+pub struct EventProcessingContext {
+    pub db: DatabaseConnection,
+    pub twitch_manager: Arc<TwitchManager>,
+    pub script_handle: ScriptExecutorHandle,
+    pub event_sender: broadcast::Sender<EventMessage>,
+    pub app_data: AppDataStore,
+    pub runtime_app_data: RuntimeAppDataStore,
+    pub chat_activity: ChatActivityTracker,
+    pub seen_viewers: SeenViewerTracker,
+    pub cooldowns: CooldownTracker,
+    pub groups: GroupCache,
+    pub feedback: FeedbackLimiter,
+    pub outcome_queue: OutcomeQueue,
+}
 
+pub async fn process_twitch_events(
+    ctx: EventProcessingContext,
     mut twitch_event_rx: broadcast::Receiver<TwitchEvent>,
 ) {
     while let Ok(event) = twitch_event_rx.recv().await {
         debug!("twitch event received: {:?}", event);
 
         tokio::spawn({
-            let db = db.clone();
-            let twitch_manager = twitch_manager.clone();
-            let script_handle = script_handle.clone();
-            let event_sender = event_sender.clone();
+            let ctx = ctx.clone();
 
             async move {
-                let result =
-                    process_twitch_event(db, twitch_manager, script_handle, event_sender, event)
-                        .await;
+                let result = process_twitch_event(ctx, event).await;
 
                 if let Err(err) = result {
                     debug!("failed to process twitch event: {err:?}",);
@@ -66,24 +97,91 @@ pub async fn process_twitch_events(
 }
 
 async fn process_twitch_event(
-    db: DatabaseConnection,
-    twitch_manager: Arc<TwitchManager>,
-    script_handle: ScriptExecutorHandle,
-    event_sender: broadcast::Sender<EventMessage>,
+    ctx: EventProcessingContext,
     event: TwitchEvent,
 ) -> anyhow::Result<()> {
+    let EventProcessingContext {
+        db,
+        twitch_manager,
+        runtime_app_data,
+        chat_activity,
+        seen_viewers,
+        ..
+    } = &ctx;
+
     let match_data: EventMatchingData = match event {
         // Matchable events
-        TwitchEvent::Redeem(event) => match_redeem_event(&db, event).await?,
-        TwitchEvent::CheerBits(event) => match_cheer_bits_event(&db, event).await?,
-        TwitchEvent::Follow(event) => match_follow_event(&db, event).await?,
-        TwitchEvent::Sub(event) => match_subscription_event(&db, event).await?,
-        TwitchEvent::GiftSub(event) => match_gifted_subscription_event(&db, event).await?,
-        TwitchEvent::ResubMsg(event) => match_re_subscription_event(&db, event).await?,
-        TwitchEvent::ChatMsg(event) => match_chat_event(&db, event).await?,
-        TwitchEvent::Raid(event) => match_raid_event(&db, event).await?,
-        TwitchEvent::AdBreakBegin(event) => match_ad_break_event(&db, event).await?,
-        TwitchEvent::ShoutoutReceive(event) => match_shoutout_receive_event(&db, event).await?,
+        TwitchEvent::Redeem(event) => match_redeem_event(db, event).await?,
+        TwitchEvent::CheerBits(event) => match_cheer_bits_event(db, event).await?,
+        TwitchEvent::Follow(event) => match_follow_event(db, event).await?,
+        TwitchEvent::Sub(event) => {
+            twitch_manager.clear_subscription(&event.user_id).await;
+            match_subscription_event(db, event).await?
+        }
+        TwitchEvent::GiftSub(event) => match_gifted_subscription_event(db, event).await?,
+        TwitchEvent::ResubMsg(event) => {
+            twitch_manager.clear_subscription(&event.user_id).await;
+            match_re_subscription_event(db, event).await?
+        }
+        TwitchEvent::ChatMsg(event) => {
+            // Messages sent by the app itself are not chat activity
+            if !twitch_manager.is_chat_sender(&event.user_id).await {
+                chat_activity.record_message(event.user_id.clone());
+            }
+
+            // Built in pause commands are handled before any other commands
+            if handle_pause_chat_command(&ctx, &event).await? {
+                return Ok(());
+            }
+
+            match_chat_event(db, twitch_manager, seen_viewers, event).await?
+        }
+        TwitchEvent::Raid(event) => match_raid_event(db, event).await?,
+        TwitchEvent::AdBreakBegin(event) => match_ad_break_event(db, event).await?,
+        TwitchEvent::ShoutoutReceive(event) => match_shoutout_receive_event(db, event).await?,
+        TwitchEvent::HypeTrainBegin(event) => {
+            match_hype_train_event(db, EventTriggerType::HypeTrainBegin, event).await?
+        }
+        TwitchEvent::HypeTrainProgress(event) => {
+            match_hype_train_event(db, EventTriggerType::HypeTrainProgress, event).await?
+        }
+        TwitchEvent::HypeTrainLevelUp(event) => {
+            match_hype_train_event(db, EventTriggerType::HypeTrainLevelUp, event).await?
+        }
+        TwitchEvent::HypeTrainEnd(event) => {
+            match_hype_train_event(db, EventTriggerType::HypeTrainEnd, event).await?
+        }
+        TwitchEvent::PollBegin(event) => {
+            match_poll_event(db, EventTriggerType::PollBegin, event).await?
+        }
+        TwitchEvent::PollEnd(event) => {
+            match_poll_event(db, EventTriggerType::PollEnd, event).await?
+        }
+        TwitchEvent::PredictionBegin(event) => {
+            match_prediction_event(db, EventTriggerType::PredictionBegin, event).await?
+        }
+        TwitchEvent::PredictionLock(event) => {
+            match_prediction_event(db, EventTriggerType::PredictionLock, event).await?
+        }
+        TwitchEvent::PredictionEnd(event) => {
+            match_prediction_event(db, EventTriggerType::PredictionEnd, event).await?
+        }
+        TwitchEvent::StreamOnline(event) => {
+            seen_viewers.set_stream(Some(event.stream_id.clone()));
+            twitch_manager
+                .set_current_stream_id(Some(event.stream_id))
+                .await;
+            runtime_app_data.write(|data| data.is_live = true).await;
+
+            match_stream_event(db, EventTriggerType::StreamOnline).await?
+        }
+        TwitchEvent::StreamOffline => {
+            seen_viewers.set_stream(None);
+            twitch_manager.set_current_stream_id(None).await;
+            runtime_app_data.write(|data| data.is_live = false).await;
+
+            match_stream_event(db, EventTriggerType::StreamOffline).await?
+        }
 
         // Internal events
         TwitchEvent::ModeratorsChanged => {
@@ -101,143 +199,124 @@ async fn process_twitch_event(
             twitch_manager.load_rewards_list().await?;
             return Ok(());
         }
+        TwitchEvent::LiveStateLoaded(stream_id) => {
+            let is_live = stream_id.is_some();
+            debug!("loaded live state: {is_live}");
+            seen_viewers.set_stream(stream_id);
+            runtime_app_data.write(|data| data.is_live = is_live).await;
+
+            // Live state is loaded once authenticated, resume any reward pauses
+            // for events that are still on cooldown from before a restart
+            if let Err(err) = restore_reward_cooldown_pauses(&ctx).await {
+                error!("failed to restore reward cooldown pauses: {err:?}");
+            }
+
+            return Ok(());
+        }
         TwitchEvent::Reset => {
             debug!("resetting twitch manager");
             twitch_manager.reset().await;
+            runtime_app_data.write(|data| data.is_live = false).await;
             return Ok(());
         }
     };
 
-    let command_futures =
-        match_data
-            .commands
-            .into_iter()
-            .map(|command| -> BoxFuture<'_, anyhow::Result<()>> {
-                Box::pin(execute_command(
-                    &db,
-                    &script_handle,
-                    &twitch_manager,
-                    command,
-                    match_data.event_data.clone(),
-                ))
-            });
-
-    let event_futures =
-        match_data
-            .events
-            .into_iter()
-            .map(|event| -> BoxFuture<'_, anyhow::Result<()>> {
-                Box::pin(execute_event(
-                    &db,
-                    &twitch_manager,
-                    &script_handle,
-                    &event_sender,
-                    event,
-                    match_data.event_data.clone(),
-                ))
-            });
-
-    let mut futures = command_futures
-        .chain(event_futures)
+    // Redemption to settle once all the events for the redeem have run
+    let redemption =
+        EventRedemption::from_events(&match_data.events, &match_data.event_data.input_data);
+
+    let command_futures = match_data
+        .commands
+        .into_iter()
+        .map(|command| -> BoxFuture<'_, anyhow::Result<()>> {
+            Box::pin(execute_command(
+                &ctx,
+                command,
+                match_data.event_data.clone(),
+            ))
+        })
         .collect::<FuturesUnordered<BoxFuture<'_, anyhow::Result<()>>>>();
 
+    let event_futures = match_data
+        .events
+        .into_iter()
+        .map(|event| -> BoxFuture<'_, anyhow::Result<EventRun>> {
+            let mut event_data = match_data.event_data.clone();
+
+            // Provide the event specific chat message capture groups
+            apply_chat_message_captures(&event.trigger, &mut event_data.input_data);
+
+            Box::pin(execute_event(&ctx, event, event_data))
+        })
+        .collect::<FuturesUnordered<BoxFuture<'_, anyhow::Result<EventRun>>>>();
+
     use futures::StreamExt;
 
-    while let Some(result) = futures.next().await {
+    let (_, event_results) = join!(
+        command_futures.for_each(|result| async move {
+            if let Err(err) = result {
+                error!("error while executing command outcome: {err:?}");
+            }
+        }),
+        event_futures.collect::<Vec<anyhow::Result<EventRun>>>()
+    );
+
+    for result in &event_results {
         if let Err(err) = result {
             error!("error while executing event outcome: {err:?}");
         }
     }
 
-    Ok(())
-}
-
-pub fn is_cooldown_elapsed(
-    execution_time: DateTimeUtc,
-    current_time: DateTimeUtc,
-    cooldown: u32,
-) -> anyhow::Result<bool> {
-    let cooldown_end_time = execution_time
-        .checked_add_signed(TimeDelta::milliseconds(cooldown as i64))
-        .context("cooldown finishes too far in the future to compute")?;
+    if let Some(redemption) = redemption {
+        redemption.settle(twitch_manager, &event_results).await;
+    }
 
-    Ok(current_time > cooldown_end_time)
+    Ok(())
 }
 
-pub async fn is_command_cooldown_elapsed(
-    db: &DatabaseConnection,
+/// Records an execution of the command by the user if the command cooldown
+/// has elapsed, otherwise the remaining cooldown is returned as the error
+///
+/// The returned charge can be rolled back if the command does not run,
+/// [None] when there is no cooldown to record
+pub fn start_command_cooldown(
+    cooldowns: &CooldownTracker,
     command: &CommandModel,
     user: &TwitchEventUser,
     current_time: DateTimeUtc,
-) -> anyhow::Result<bool> {
+) -> Result<Option<CooldownCharge>, Duration> {
     let cooldown = &command.cooldown;
+    let duration = if cooldown.enabled {
+        cooldown.duration
+    } else {
+        0
+    };
 
-    // No cooldown enabled
-    if !cooldown.enabled {
-        return Ok(true);
-    }
-
-    // Handle global cooldown (Check last execution)
-    if !cooldown.per_user {
-        let last_execution = command
-            .last_execution(db, 0)
-            .await
-            .context("failed to request last execution for command")?;
-
-        let last_execution = match last_execution {
-            Some(value) => value,
-            None => return Ok(true),
-        };
-
-        return is_cooldown_elapsed(last_execution.created_at, current_time, cooldown.duration);
-    }
-
-    let mut offset = 0;
-
-    loop {
-        let last_execution = command
-            .last_execution(db, offset)
-            .await
-            .context("failed to request last execution for command")?;
-
-        // Cooldown elapsed if no more to check
-        let last_execution = match last_execution {
-            Some(value) => value,
-            None => return Ok(true),
-        };
-
-        // Check if the execution has elapsed the cooldown
-        let last_execution_elapsed =
-            is_cooldown_elapsed(last_execution.created_at, current_time, cooldown.duration)?;
-
-        // Execution was not from the target user
-        if last_execution
-            .metadata
-            .user
-            .is_none_or(|event_user| event_user.id != user.id)
-        {
-            offset += 1;
-
-            // If the execution had elapsed the cooldown we can treat the rest as elapsed too (We can skip checking the rest)
-            if last_execution_elapsed {
-                return Ok(true);
-            }
-
-            continue;
-        }
-
-        // Found an execution
-        return Ok(last_execution_elapsed);
-    }
+    cooldowns.try_record_execution(
+        CooldownTarget::Command(command.id),
+        Some(&user.id),
+        duration,
+        cooldown.per_user,
+        current_time,
+    )
 }
 
 pub async fn execute_command(
-    db: &DatabaseConnection,
-    script_handle: &ScriptExecutorHandle,
-    twitch_manager: &Arc<TwitchManager>,
-    command: CommandWithContext,
+    ctx: &EventProcessingContext,
+    mut command: CommandWithContext,
     event_data: EventData,
 ) -> anyhow::Result<()> {
+    let EventProcessingContext {
+        db,
+        script_handle,
+        twitch_manager,
+        app_data,
+        cooldowns,
+        feedback,
+        groups,
+        ..
+    } = ctx;
+
     let EventInputData::Chat {
         message,
         message_id,
@@ -252,24 +331,89 @@ pub async fn execute_command(
         None => return Err(anyhow!("got chat event without a user")),
     };
 
-    // Ensure required role is present
-    if !has_required_role(
-        twitch_manager,
-        Some(user.id.clone()),
-        &command.command.require_role,
-    )
-    .await
+    // Apply the defaults of the group the command belongs to
+    let group_enabled = apply_command_group(db, groups, &mut command.command).await?;
+
+    let target = CooldownTarget::Command(command.command.id);
+    let feedback_replies = &command.command.feedback_replies;
+
+    // Ensure the command and its group are enabled
+    if !command.command.enabled || !group_enabled {
+        debug!("skipping command: disabled");
+        send_feedback_reply(
+            db,
+            twitch_manager,
+            feedback,
+            target,
+            feedback_replies,
+            Some(&user),
+            FeedbackReason::Disabled,
+        )
+        .await;
+        return Ok(());
+    }
+
+    // Ensure outcomes are not paused
+    if app_data.read().await.paused {
+        debug!("skipping command: outcomes paused");
+        return Ok(());
+    }
+
+    let user_access = &command.command.user_access;
+
+    // Ensure the user is not denied
+    if user_access.is_denied(user.id.as_str()) {
+        debug!("skipping command: user denied");
+        return Ok(());
+    }
+
+    // Ensure required role is present, allowed users bypass the required role
+    if !user_access.is_allowed(user.id.as_str())
+        && !has_required_role(
+            twitch_manager,
+            Some(user.id.clone()),
+            &command.command.require_role,
+        )
+        .await
     {
         debug!("skipping command: missing required role");
+        send_feedback_reply(
+            db,
+            twitch_manager,
+            feedback,
+            target,
+            feedback_replies,
+            Some(&user),
+            FeedbackReason::MissingRole {
+                role: &command.command.require_role,
+            },
+        )
+        .await;
         return Ok(());
     }
 
     let current_time = Utc::now();
 
-    if !is_command_cooldown_elapsed(db, &command.command, &user, current_time).await? {
-        debug!("skipping command: cooldown");
-        return Ok(());
-    }
+    // Cooldown is recorded as soon as it passes so messages received while
+    // the command is running are also on cooldown
+    let cooldown_charge =
+        match start_command_cooldown(cooldowns, &command.command, &user, current_time) {
+            Ok(value) => value,
+            Err(remaining) => {
+                debug!("skipping command: cooldown");
+                send_feedback_reply(
+                    db,
+                    twitch_manager,
+                    feedback,
+                    target,
+                    feedback_replies,
+                    Some(&user),
+                    FeedbackReason::Cooldown { remaining },
+                )
+                .await;
+                return Ok(());
+            }
+        };
 
     // Create metadata for storage
     let metadata = CommandExecutionMetadata {
@@ -281,31 +425,20 @@ pub async fn execute_command(
         )],
     };
 
-    match command.command.outcome {
+    let outcome_result = match command.command.outcome {
         CommandOutcome::Template { message } => {
-            let to_usr = command
-                .args
-                .first()
-                .map(|value| value.as_str())
-                .unwrap_or_default();
-            let message = message
-                .replace("$(user)", user.name.as_str())
-                .replace("$(touser)", to_usr);
-
-            if message.len() < 500 {
-                twitch_manager.send_chat_message(&message).await?;
-            } else {
-                let mut chars = message.chars();
-
-                loop {
-                    let message = chars.by_ref().take(500).collect::<String>();
-                    if message.is_empty() {
-                        break;
-                    }
+            let ctx = TemplateContext {
+                user: Some(&user),
+                input_data: Some(&event_data.input_data),
+                args: &command.args,
+                ..Default::default()
+            };
+            let message = render_template(db, &message, &ctx).await;
 
-                    twitch_manager.send_chat_message(&message).await?;
-                }
-            }
+            // Reply to the message that triggered the command
+            twitch_manager
+                .send_chat_reply(&message, message_id.clone())
+                .await
         }
         CommandOutcome::Script { script } => {
             let user = CommandContextUser {
@@ -331,8 +464,17 @@ pub async fn execute_command(
                     script,
                     ctx,
                 )
-                .await?;
+                .await
         }
+    };
+
+    if let Err(err) = outcome_result {
+        // Failed commands do not use up the cooldown
+        if let Some(charge) = cooldown_charge {
+            cooldowns.rollback(charge);
+        }
+
+        return Err(err);
     }
 
     // Store command execution
@@ -350,110 +492,213 @@ pub async fn execute_command(
     Ok(())
 }
 
-pub async fn is_event_cooldown_elapsed(
-    db: &DatabaseConnection,
+/// Get the remaining time before the event cooldown has elapsed
+/// for the user, [None] when the cooldown has elapsed
+pub fn get_event_cooldown_remaining(
+    cooldowns: &CooldownTracker,
     event: &EventModel,
     user: Option<&TwitchEventUser>,
     current_time: DateTimeUtc,
-) -> anyhow::Result<bool> {
+) -> Option<Duration> {
     let cooldown = &event.cooldown;
 
     // No cooldown enabled
     if !cooldown.enabled {
-        return Ok(true);
+        return None;
     }
 
-    // Handle global cooldown (Check last execution)
-    if !cooldown.per_user {
-        let last_execution = event
-            .last_execution(db, 0)
-            .await
-            .context("failed to request last execution for event")?;
-
-        let last_execution = match last_execution {
-            Some(value) => value,
-            None => return Ok(true),
-        };
-
-        return is_cooldown_elapsed(last_execution.created_at, current_time, cooldown.duration);
-    }
+    cooldowns.get_remaining(
+        CooldownTarget::Event(event.id),
+        user.map(|user| &user.id),
+        cooldown.duration,
+        cooldown.per_user,
+        current_time,
+    )
+}
 
-    let user = match user {
-        Some(user) => user,
-        // Anonymous users bypass the cooldown
-        None => return Ok(true),
+/// Records an execution of the event by the user if the event cooldown
+/// has elapsed, otherwise the remaining cooldown is returned as the error
+///
+/// The returned charge can be rolled back if the event does not run,
+/// [None] when there is no cooldown to record
+pub fn start_event_cooldown(
+    cooldowns: &CooldownTracker,
+    event: &EventModel,
+    user: Option<&TwitchEventUser>,
+    current_time: DateTimeUtc,
+) -> Result<Option<CooldownCharge>, Duration> {
+    let cooldown = &event.cooldown;
+    let duration = if cooldown.enabled {
+        cooldown.duration
+    } else {
+        0
     };
 
-    let mut offset = 0;
-
-    loop {
-        let last_execution = event
-            .last_execution(db, offset)
-            .await
-            .context("failed to request last execution for event")?;
+    cooldowns.try_record_execution(
+        CooldownTarget::Event(event.id),
+        user.map(|user| &user.id),
+        duration,
+        cooldown.per_user,
+        current_time,
+    )
+}
 
-        // Cooldown elapsed if no more to check
-        let last_execution = match last_execution {
-            Some(value) => value,
-            None => return Ok(true),
-        };
+/// Result of attempting to execute an event
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventRun {
+    /// Outcome of the event was produced
+    Completed,
+    /// Event was skipped without producing its outcome
+    Skipped,
+    /// Event was accepted but its outcome was cancelled before it ran
+    /// (outcomes paused, on cooldown or dropped from the outcome queue)
+    Cancelled,
+}
 
-        // Check if the execution has elapsed the cooldown
-        let last_execution_elapsed =
-            is_cooldown_elapsed(last_execution.created_at, current_time, cooldown.duration)?;
+/// Executes the outcome of an event if the event is enabled and its
+/// conditions, required role and cooldown pass
+pub async fn execute_event(
+    ctx: &EventProcessingContext,
+    mut event: EventModel,
+    event_data: EventData,
+) -> anyhow::Result<EventRun> {
+    let EventProcessingContext {
+        db,
+        twitch_manager,
+        script_handle,
+        event_sender,
+        app_data,
+        cooldowns,
+        feedback,
+        outcome_queue,
+        groups,
+        ..
+    } = ctx;
+
+    // Apply the defaults of the group the event belongs to
+    let group_enabled = apply_event_group(db, groups, &mut event).await?;
+
+    let target = CooldownTarget::Event(event.id);
+
+    // Ensure the event and its group are enabled
+    if !event.enabled || !group_enabled {
+        debug!("skipping event: disabled");
+        send_feedback_reply(
+            db,
+            twitch_manager,
+            feedback,
+            target,
+            &event.feedback_replies,
+            event_data.user.as_ref(),
+            FeedbackReason::Disabled,
+        )
+        .await;
+        return Ok(EventRun::Skipped);
+    }
 
-        // Execution was not from the target user
-        if last_execution
-            .metadata
-            .user
-            .is_none_or(|event_user| event_user.id != user.id)
-        {
-            offset += 1;
+    // Ensure outcomes are not paused
+    if app_data.read().await.paused {
+        debug!("skipping event: outcomes paused");
+        return Ok(EventRun::Cancelled);
+    }
 
-            // If the execution had elapsed the cooldown we can treat the rest as elapsed too (We can skip checking the rest)
-            if last_execution_elapsed {
-                return Ok(true);
-            }
+    // Ensure event conditions are met
+    if !is_event_conditions_met(&event.conditions, &event_data.input_data)? {
+        debug!("skipping event: conditions not met");
+        return Ok(EventRun::Skipped);
+    }
 
-            continue;
-        }
+    let user_id = event_data.user.as_ref().map(|value| value.id.as_str());
 
-        // Found an execution
-        return Ok(last_execution_elapsed);
+    // Ensure the user is not denied
+    if user_id.is_some_and(|user_id| event.user_access.is_denied(user_id)) {
+        debug!("skipping event: user denied");
+        return Ok(EventRun::Skipped);
     }
-}
 
-pub async fn execute_event(
-    db: &DatabaseConnection,
-    twitch_manager: &Arc<TwitchManager>,
-    script_handle: &ScriptExecutorHandle,
+    // Allowed users bypass the required role
+    let is_allowed_user = user_id.is_some_and(|user_id| event.user_access.is_allowed(user_id));
 
-    event_sender: &broadcast::Sender<EventMessage>,
-    event: EventModel,
-    event_data: EventData,
-) -> anyhow::Result<()> {
     // Ensure required role is present
-    if !has_required_role(
-        twitch_manager,
-        event_data.user.as_ref().map(|value| value.id.clone()),
-        &event.require_role,
-    )
-    .await
+    if !is_allowed_user
+        && !has_required_role(
+            twitch_manager,
+            event_data.user.as_ref().map(|value| value.id.clone()),
+            &event.require_role,
+        )
+        .await
     {
         debug!("skipping event: missing required role");
-        return Ok(());
+        send_feedback_reply(
+            db,
+            twitch_manager,
+            feedback,
+            target,
+            &event.feedback_replies,
+            event_data.user.as_ref(),
+            FeedbackReason::MissingRole {
+                role: &event.require_role,
+            },
+        )
+        .await;
+        return Ok(EventRun::Skipped);
     }
 
     let current_time = Utc::now();
 
-    // Ensure cooldown is not active
-    if !is_event_cooldown_elapsed(db, &event, event_data.user.as_ref(), current_time).await? {
-        debug!("skipping event: cooldown");
-        return Ok(());
+    // Ensure cooldown is not active, the cooldown is recorded as soon as it
+    // passes so events received during the outcome delay are also on cooldown
+    let cooldown_charge =
+        match start_event_cooldown(cooldowns, &event, event_data.user.as_ref(), current_time) {
+            Ok(value) => value,
+            Err(remaining) => {
+                debug!("skipping event: cooldown");
+                send_feedback_reply(
+                    db,
+                    twitch_manager,
+                    feedback,
+                    target,
+                    &event.feedback_replies,
+                    event_data.user.as_ref(),
+                    FeedbackReason::Cooldown { remaining },
+                )
+                .await;
+                return Ok(EventRun::Cancelled);
+            }
+        };
+
+    // Pause the reward for the remaining cooldown
+    let mut rollback_pause = None;
+    if let (Some(_), Some(reward_id)) = (
+        &cooldown_charge,
+        get_cooldown_reward_id(&event.trigger, &event.cooldown),
+    ) {
+        if let Some(remaining) = get_event_cooldown_remaining(cooldowns, &event, None, current_time)
+        {
+            let (tx, rx) = oneshot::channel();
+            rollback_pause = Some(tx);
+            spawn_reward_cooldown_pause(
+                twitch_manager.clone(),
+                reward_id.to_string(),
+                remaining,
+                RewardCooldownPause::Started(rx),
+            );
+        }
     }
 
+    // Cooldowns are only used up by outcomes that run
+    let rollback_cooldown = || {
+        if let Some(charge) = cooldown_charge {
+            cooldowns.rollback(charge);
+        }
+
+        if let Some(rollback_pause) = rollback_pause {
+            _ = rollback_pause.send(());
+        }
+    };
+
     // Create metadata for storage
-    let metadata = EventExecutionMetadata {
+    let mut metadata = EventExecutionMetadata {
         user: event_data.user.clone(),
         data: vec![(
             "input_data".to_string(),
@@ -467,14 +712,65 @@ pub async fn execute_event(
 
     let event_id = event.id;
 
-    // Produce outcome message and send it
-    if let Some(msg) =
-        produce_outcome_message(db, twitch_manager, script_handle, event, event_data).await?
+    // Wait for a slot in the outcome queue
+    let queue_config = app_data.read().await.outcome_queue_config.clone();
+    let permit = match outcome_queue
+        .acquire(OutcomeLane::from_outcome(&event.outcome), &queue_config)
+        .await
     {
-        _ = event_sender.send(msg);
+        Some(value) => value,
+        None => {
+            debug!("skipping event: dropped from outcome queue");
+            rollback_cooldown();
+            return Ok(EventRun::Cancelled);
+        }
+    };
+
+    // Outcomes may have been paused while waiting in the queue
+    if app_data.read().await.paused {
+        debug!("skipping event: outcomes paused");
+        rollback_cooldown();
+        return Ok(EventRun::Cancelled);
+    }
+
+    // Messages from timers are sent after any other queued messages
+    let chat_priority = match event.trigger {
+        EventTrigger::Timer { .. } => ChatPriority::Low,
+        _ => ChatPriority::Normal,
+    };
+
+    let outcome_ctx = OutcomeContext {
+        db,
+        twitch_manager,
+        script_handle,
+        event_sender,
+        chat_priority,
+    };
+
+    // Produce outcome message and send it
+    let outcome_result =
+        produce_outcome_message(outcome_ctx, event_id, event.outcome, event_data).await;
+
+    permit.release_after(Duration::from_millis(queue_config.outcome_spacing as u64));
+
+    // Store any additional outcome metadata, outcomes that failed after
+    // partially running still have metadata for the parts that ran
+    if let Some(outcome_metadata) = get_outcome_metadata(&outcome_result) {
+        metadata
+            .data
+            .push(("outcome".to_string(), outcome_metadata));
     }
 
-    // Store event execution
+    if let Err(err) = &outcome_result {
+        rollback_cooldown();
+        metadata.data.push((
+            "error".to_string(),
+            serde_json::Value::String(err.to_string()),
+        ));
+    }
+
+    // Store event execution, failed executions are stored so the
+    // partially run outcome is recorded
     EventExecutionModel::create(
         db,
         CreateEventExecution {
@@ -486,9 +782,245 @@ pub async fn execute_event(
     .await
     .context("failed to store last event execution")?;
 
+    outcome_result?;
+
+    Ok(EventRun::Completed)
+}
+
+/// Get the ID of the reward that should be paused while the event
+/// is on cooldown, only applies to redeem events with a global cooldown
+pub fn get_cooldown_reward_id<'a>(
+    trigger: &'a EventTrigger,
+    cooldown: &EventCooldown,
+) -> Option<&'a str> {
+    if !cooldown.enabled || cooldown.per_user || cooldown.duration == 0 {
+        return None;
+    }
+
+    match trigger {
+        EventTrigger::Redeem { reward_id, .. } => Some(reward_id),
+        _ => None,
+    }
+}
+
+/// How a reward pause for an event cooldown was started
+enum RewardCooldownPause {
+    /// Cooldown was just recorded, the pause ends early if the
+    /// cooldown is rolled back
+    Started(oneshot::Receiver<()>),
+    /// Cooldown was recorded before the app was restarted
+    Restored,
+}
+
+/// Pauses a reward on Twitch for the provided duration, only unpausing
+/// it afterwards if the reward was paused by this cooldown
+fn spawn_reward_cooldown_pause(
+    twitch_manager: Arc<TwitchManager>,
+    reward_id: String,
+    duration: Duration,
+    pause: RewardCooldownPause,
+) {
+    tokio::spawn(async move {
+        let (paused, rolled_back) = match pause {
+            RewardCooldownPause::Started(rolled_back) => (
+                twitch_manager.pause_reward_for_cooldown(&reward_id).await,
+                Some(rolled_back),
+            ),
+            RewardCooldownPause::Restored => (
+                twitch_manager
+                    .restore_reward_cooldown_pause(&reward_id)
+                    .await,
+                None,
+            ),
+        };
+
+        match paused {
+            Ok(true) => {}
+            // Reward was already paused or is not owned by the app
+            Ok(false) => return,
+            Err(err) => {
+                error!("failed to pause reward during cooldown: {err:?}");
+                return;
+            }
+        }
+
+        // The rollback sender is dropped without sending once the
+        // cooldown has been used up by the outcome
+        let rolled_back = async move {
+            let is_rolled_back = match rolled_back {
+                Some(rolled_back) => rolled_back.await.is_ok(),
+                None => false,
+            };
+
+            if !is_rolled_back {
+                std::future::pending::<()>().await;
+            }
+        };
+
+        tokio::select! {
+            _ = tokio::time::sleep(duration) => {}
+            _ = rolled_back => debug!("cooldown rolled back, unpausing reward early"),
+        }
+
+        if let Err(err) = twitch_manager
+            .resume_reward_after_cooldown(&reward_id)
+            .await
+        {
+            error!("failed to unpause reward after cooldown: {err:?}");
+        }
+    });
+}
+
+/// Pauses rewards for any redeem events that still have an active
+/// global cooldown, used to ensure rewards paused before the app was
+/// closed will be unpaused
+async fn restore_reward_cooldown_pauses(ctx: &EventProcessingContext) -> anyhow::Result<()> {
+    let events = EventModel::get_by_trigger_type(&ctx.db, EventTriggerType::Redeem).await?;
+    let current_time = Utc::now();
+
+    for mut event in events {
+        apply_event_group(&ctx.db, &ctx.groups, &mut event).await?;
+
+        let reward_id = match get_cooldown_reward_id(&event.trigger, &event.cooldown) {
+            Some(value) => value,
+            None => continue,
+        };
+
+        // Cooldown has already elapsed
+        let remaining =
+            match get_event_cooldown_remaining(&ctx.cooldowns, &event, None, current_time) {
+                Some(value) => value,
+                None => continue,
+            };
+
+        spawn_reward_cooldown_pause(
+            ctx.twitch_manager.clone(),
+            reward_id.to_string(),
+            remaining,
+            RewardCooldownPause::Restored,
+        );
+    }
+
     Ok(())
 }
 
+/// Applies the default cooldown and required role of the group the event
+/// belongs to, returns whether the group is enabled
+pub async fn apply_event_group(
+    db: &DatabaseConnection,
+    groups: &GroupCache,
+    event: &mut EventModel,
+) -> anyhow::Result<bool> {
+    let group = match groups.get_optional(db, event.group_id).await? {
+        Some(value) => value,
+        None => return Ok(true),
+    };
+
+    group.apply_member_defaults(
+        &mut event.require_role,
+        &mut event.cooldown.enabled,
+        &mut event.cooldown.duration,
+    );
+
+    Ok(group.enabled)
+}
+
+/// Applies the default cooldown and required role of the group the command
+/// belongs to, returns whether the group is enabled
+pub async fn apply_command_group(
+    db: &DatabaseConnection,
+    groups: &GroupCache,
+    command: &mut CommandModel,
+) -> anyhow::Result<bool> {
+    let group = match groups.get_optional(db, command.group_id).await? {
+        Some(value) => value,
+        None => return Ok(true),
+    };
+
+    group.apply_member_defaults(
+        &mut command.require_role,
+        &mut command.cooldown.enabled,
+        &mut command.cooldown.duration,
+    );
+
+    Ok(group.enabled)
+}
+
+/// Channel point redemption that should be fulfilled or refunded
+/// once all the events for the redeem have run
+struct EventRedemption {
+    reward_id: String,
+    redemption_id: RedemptionId,
+    auto_fulfill: bool,
+    auto_refund: bool,
+}
+
+impl EventRedemption {
+    /// Creates the redemption for the events matched by a redeem, the
+    /// redemption is fulfilled or refunded if any of the events enable it
+    fn from_events(events: &[EventModel], input_data: &EventInputData) -> Option<Self> {
+        let (reward_id, redemption_id) = match input_data {
+            EventInputData::Redeem {
+                reward_id,
+                redemption_id: Some(redemption_id),
+                ..
+            } => (reward_id.clone(), redemption_id.clone()),
+            _ => return None,
+        };
+
+        let (auto_fulfill, auto_refund) =
+            events
+                .iter()
+                .fold((false, false), |(fulfill, refund), event| {
+                    match &event.trigger {
+                        EventTrigger::Redeem {
+                            auto_fulfill,
+                            auto_refund,
+                            ..
+                        } => (fulfill || *auto_fulfill, refund || *auto_refund),
+                        _ => (fulfill, refund),
+                    }
+                });
+
+        if !auto_fulfill && !auto_refund {
+            return None;
+        }
+
+        Some(Self {
+            reward_id,
+            redemption_id,
+            auto_fulfill,
+            auto_refund,
+        })
+    }
+
+    /// Settles the redemption from the results of every event for the redeem,
+    /// the redemption is fulfilled if any event completed otherwise it is
+    /// refunded if any event was cancelled or failed. Redemptions where
+    /// every event was skipped are left alone
+    async fn settle(&self, twitch_manager: &TwitchManager, results: &[anyhow::Result<EventRun>]) {
+        let completed = results
+            .iter()
+            .any(|result| matches!(result, Ok(EventRun::Completed)));
+        let failed = results
+            .iter()
+            .any(|result| matches!(result, Ok(EventRun::Cancelled) | Err(_)));
+
+        let status = match (completed, failed) {
+            (true, _) if self.auto_fulfill => CustomRewardRedemptionStatus::Fulfilled,
+            (false, true) if self.auto_refund => CustomRewardRedemptionStatus::Canceled,
+            _ => return,
+        };
+
+        if let Err(err) = twitch_manager
+            .update_redemption_status(&self.reward_id, &self.redemption_id, status)
+            .await
+        {
+            error!("failed to update redemption status: {err:?}");
+        }
+    }
+}
+
 pub async fn has_required_role(
     twitch_manager: &TwitchManager,
     user_id: Option<UserId>,
@@ -542,5 +1074,29 @@ pub async fn has_required_role(
             .is_ok_and(|value| value.is_some());
     }
 
-    false
+    // Check the user is subscribed at the minimum tier
+    let min_tier_level = match required_role {
+        MinimumRequireRole::Subscriber => 1,
+        MinimumRequireRole::SubscriberTier2 => 2,
+        MinimumRequireRole::SubscriberTier3 => 3,
+        _ => return false,
+    };
+
+    twitch_manager
+        .get_subscription_tier(user)
+        .await
+        .is_ok_and(|tier| {
+            tier.is_some_and(|tier| get_subscription_tier_level(&tier) >= min_tier_level)
+        })
+}
+
+/// Get a numeric level for a subscription tier for comparing tiers,
+/// prime subscriptions are treated as tier 1
+fn get_subscription_tier_level(tier: &SubscriptionTier) -> u8 {
+    match tier {
+        SubscriptionTier::Tier1 | SubscriptionTier::Prime => 1,
+        SubscriptionTier::Tier2 => 2,
+        SubscriptionTier::Tier3 => 3,
+        _ => 0,
+    }
 }
@@ -0,0 +1,402 @@
+//! # Outcome Queue
+//!
+//! Queue between matched events and producing their outcomes, limits how
+//! many outcomes can run at once and how many can be waiting to run
+//!
+//! An outcome is considered running until the outcome spacing has passed
+//! after it was produced, the overlay does not report when it has finished
+//! playing an outcome
+
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use tokio::sync::oneshot;
+
+use crate::{
+    database::entity::events::EventOutcome,
+    state::{
+        app_data::{OutcomeQueueConfig, QueueDropPolicy},
+        runtime_app_data::RuntimeAppDataStore,
+    },
+};
+
+/// Type of outcome, each type can be queued separately
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OutcomeLane {
+    /// Throwing items
+    Throw,
+    /// Playing sounds
+    Sound,
+    /// Sending chat messages
+    Chat,
+    /// Hotkeys and scripts
+    Other,
+}
+
+impl OutcomeLane {
+    /// Get the lane for an outcome, outcomes made up of other outcomes use
+    /// the lane of their nested outcomes. When the nested outcomes use
+    /// different lanes the first lane from [OutcomeLane::PRIORITY] is used
+    /// so throws are always limited by the throw lane
+    pub fn from_outcome(outcome: &EventOutcome) -> Self {
+        match outcome {
+            EventOutcome::ThrowBits(_)
+            | EventOutcome::Throwable(_)
+            | EventOutcome::ChannelEmotes(_) => Self::Throw,
+            EventOutcome::PlaySound(_) => Self::Sound,
+            EventOutcome::SendChatMessage(_) => Self::Chat,
+            EventOutcome::TriggerHotkey(_) | EventOutcome::Script(_) => Self::Other,
+            EventOutcome::Sequence(sequence) => {
+                Self::from_outcomes(sequence.steps.iter().map(|step| &step.outcome))
+            }
+            EventOutcome::Random(random) => {
+                Self::from_outcomes(random.choices.iter().map(|choice| &choice.outcome))
+            }
+        }
+    }
+
+    /// Order lanes are chosen in when nested outcomes use different lanes
+    const PRIORITY: [Self; 4] = [Self::Throw, Self::Sound, Self::Chat, Self::Other];
+
+    /// Get the lane for a collection of nested outcomes
+    fn from_outcomes<'a>(outcomes: impl Iterator<Item = &'a EventOutcome>) -> Self {
+        let lanes: Vec<Self> = outcomes.map(Self::from_outcome).collect();
+
+        Self::PRIORITY
+            .into_iter()
+            .find(|lane| lanes.contains(lane))
+            .unwrap_or(Self::Other)
+    }
+}
+
+/// Key for a lane in the queue, [None] when all outcomes share the same lane
+type LaneKey = Option<OutcomeLane>;
+
+/// Shared queue for outcomes
+#[derive(Clone)]
+pub struct OutcomeQueue {
+    inner: Arc<OutcomeQueueInner>,
+}
+
+struct OutcomeQueueInner {
+    /// Current state of each lane
+    lanes: Mutex<HashMap<LaneKey, LaneState>>,
+    /// Runtime app data to report the queue depth to
+    runtime_app_data: RuntimeAppDataStore,
+}
+
+#[derive(Default)]
+struct LaneState {
+    /// Number of outcomes currently running
+    running: usize,
+    /// Maximum number of outcomes that can run at once
+    limit: Option<usize>,
+    /// Outcomes waiting to run, oldest first
+    waiting: VecDeque<oneshot::Sender<OutcomePermit>>,
+}
+
+/// Permit to run an outcome, the slot is released when the permit is dropped
+pub struct OutcomePermit {
+    inner: Option<Arc<OutcomeQueueInner>>,
+    key: LaneKey,
+}
+
+impl OutcomeQueue {
+    pub fn new(runtime_app_data: RuntimeAppDataStore) -> Self {
+        Self {
+            inner: Arc::new(OutcomeQueueInner {
+                lanes: Default::default(),
+                runtime_app_data,
+            }),
+        }
+    }
+
+    /// Waits for a slot to run an outcome in the provided lane, returns [None]
+    /// if the outcome was dropped from the queue
+    pub async fn acquire(
+        &self,
+        lane: OutcomeLane,
+        config: &OutcomeQueueConfig,
+    ) -> Option<OutcomePermit> {
+        let key = config.separate_lanes.then_some(lane);
+
+        let rx = {
+            let lanes = &mut *self
+                .inner
+                .lanes
+                .lock()
+                .expect("outcome queue lock poisoned");
+            let lane_state = lanes.entry(key).or_default();
+            lane_state.limit = config.concurrency_limit();
+
+            // Start any outcomes that can run with the current limit
+            lane_state.start_waiting(|| self.inner.create_permit(key));
+
+            if lane_state.try_start() {
+                return Some(self.inner.create_permit(key));
+            }
+
+            lane_state.push_waiting(config.max_length, config.drop_policy)?
+        };
+
+        self.inner.report_depth();
+
+        let permit = rx.await.ok();
+        self.inner.report_depth();
+        permit
+    }
+
+    /// Drops all outcomes waiting in the queue
+    pub fn flush(&self) {
+        {
+            let lanes = &mut *self
+                .inner
+                .lanes
+                .lock()
+                .expect("outcome queue lock poisoned");
+
+            for lane_state in lanes.values_mut() {
+                lane_state.waiting.clear();
+            }
+        }
+
+        self.inner.report_depth();
+    }
+}
+
+impl OutcomeQueueInner {
+    /// Creates a permit for a running slot in the lane
+    fn create_permit(self: &Arc<Self>, key: LaneKey) -> OutcomePermit {
+        OutcomePermit {
+            inner: Some(self.clone()),
+            key,
+        }
+    }
+
+    /// Total number of outcomes waiting across all lanes
+    fn depth(&self) -> usize {
+        let lanes = &*self.lanes.lock().expect("outcome queue lock poisoned");
+        lanes.values().map(|lane| lane.waiting.len()).sum()
+    }
+
+    /// Reports the current queue depth to the runtime app data
+    fn report_depth(self: &Arc<Self>) {
+        let inner = self.clone();
+        tokio::spawn(async move {
+            inner
+                .runtime_app_data
+                .write(|runtime_app_data| runtime_app_data.outcome_queue_depth = inner.depth())
+                .await;
+        });
+    }
+
+    /// Releases a running slot in the lane
+    fn release(self: &Arc<Self>, key: LaneKey) {
+        {
+            let lanes = &mut *self.lanes.lock().expect("outcome queue lock poisoned");
+            let lane_state = lanes.entry(key).or_default();
+            lane_state.running = lane_state.running.saturating_sub(1);
+            lane_state.start_waiting(|| self.create_permit(key));
+        }
+
+        self.report_depth();
+    }
+}
+
+impl LaneState {
+    fn has_capacity(&self) -> bool {
+        match self.limit {
+            Some(limit) => self.running < limit,
+            None => true,
+        }
+    }
+
+    /// Starts running an outcome right away when nothing is waiting
+    /// and there is capacity, returns whether the outcome started
+    fn try_start(&mut self) -> bool {
+        if !self.waiting.is_empty() || !self.has_capacity() {
+            return false;
+        }
+
+        self.running += 1;
+        true
+    }
+
+    /// Adds an outcome to the waiting queue, when the queue is full the
+    /// `drop_policy` is applied. Returns [None] if the new outcome was dropped
+    fn push_waiting(
+        &mut self,
+        max_length: usize,
+        drop_policy: QueueDropPolicy,
+    ) -> Option<oneshot::Receiver<OutcomePermit>> {
+        if max_length != 0 && self.waiting.len() >= max_length {
+            match drop_policy {
+                QueueDropPolicy::DropNewest => return None,
+                QueueDropPolicy::DropOldest => {
+                    // Dropping the sender lets the waiting outcome know it was dropped
+                    self.waiting.pop_front();
+                }
+            }
+        }
+
+        let (tx, rx) = oneshot::channel();
+        self.waiting.push_back(tx);
+        Some(rx)
+    }
+
+    /// Hands out permits to waiting outcomes while there is capacity
+    fn start_waiting(&mut self, create_permit: impl Fn() -> OutcomePermit) {
+        while self.has_capacity() {
+            let tx = match self.waiting.pop_front() {
+                Some(value) => value,
+                None => break,
+            };
+
+            match tx.send(create_permit()) {
+                Ok(_) => self.running += 1,
+                // Outcome stopped waiting, the permit must not release a slot
+                Err(mut permit) => permit.inner = None,
+            }
+        }
+    }
+}
+
+impl OutcomePermit {
+    /// Releases the slot after the provided delay, the slot is released
+    /// right away when there is no delay
+    pub fn release_after(self, delay: Duration) {
+        if delay.is_zero() {
+            return;
+        }
+
+        tokio::spawn(async move {
+            tokio::time::sleep(delay).await;
+            drop(self);
+        });
+    }
+}
+
+impl Drop for OutcomePermit {
+    fn drop(&mut self) {
+        if let Some(inner) = self.inner.take() {
+            inner.release(self.key);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use tokio::sync::oneshot::error::TryRecvError;
+    use uuid::Uuid;
+
+    use super::{LaneState, OutcomeLane, OutcomePermit};
+    use crate::{
+        database::entity::events::{
+            EventOutcome, EventOutcomePlaySound, EventOutcomeRandom, EventOutcomeRandomChoice,
+            EventOutcomeSequence, EventOutcomeSequenceStep, EventOutcomeTriggerHotkey,
+        },
+        state::app_data::QueueDropPolicy,
+    };
+
+    /// Permit that does not belong to a queue
+    fn detached_permit() -> OutcomePermit {
+        OutcomePermit {
+            inner: None,
+            key: None,
+        }
+    }
+
+    fn lane(limit: Option<usize>) -> LaneState {
+        LaneState {
+            limit,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_drop_newest() {
+        let mut lane = lane(Some(1));
+        assert!(lane.try_start());
+        assert!(!lane.try_start());
+
+        let mut first = lane.push_waiting(1, QueueDropPolicy::DropNewest).unwrap();
+        assert!(lane.push_waiting(1, QueueDropPolicy::DropNewest).is_none());
+
+        assert_eq!(lane.waiting.len(), 1);
+        assert!(matches!(first.try_recv(), Err(TryRecvError::Empty)));
+    }
+
+    #[test]
+    fn test_drop_oldest() {
+        let mut lane = lane(Some(1));
+        assert!(lane.try_start());
+
+        let mut first = lane.push_waiting(1, QueueDropPolicy::DropOldest).unwrap();
+        let mut second = lane.push_waiting(1, QueueDropPolicy::DropOldest).unwrap();
+
+        // Oldest outcome is told it was dropped
+        assert_eq!(lane.waiting.len(), 1);
+        assert!(matches!(first.try_recv(), Err(TryRecvError::Closed)));
+        assert!(matches!(second.try_recv(), Err(TryRecvError::Empty)));
+    }
+
+    #[test]
+    fn test_limit_handoff() {
+        let mut lane = lane(Some(1));
+        assert!(lane.try_start());
+
+        let mut first = lane.push_waiting(0, QueueDropPolicy::DropNewest).unwrap();
+        let mut second = lane.push_waiting(0, QueueDropPolicy::DropNewest).unwrap();
+
+        // Nothing starts while the lane is full
+        lane.start_waiting(detached_permit);
+        assert!(first.try_recv().is_err());
+
+        // Releasing the running outcome hands the slot to the oldest waiting
+        lane.running -= 1;
+        lane.start_waiting(detached_permit);
+        assert!(first.try_recv().is_ok());
+        assert!(second.try_recv().is_err());
+        assert_eq!(lane.running, 1);
+
+        // Raising the limit starts the remaining outcomes
+        lane.limit = Some(2);
+        lane.start_waiting(detached_permit);
+        assert!(second.try_recv().is_ok());
+        assert_eq!(lane.running, 2);
+    }
+
+    #[test]
+    fn test_nested_outcome_lane() {
+        let hotkey = || {
+            EventOutcome::TriggerHotkey(EventOutcomeTriggerHotkey {
+                hotkey_id: String::new(),
+            })
+        };
+        let sound = || {
+            EventOutcome::PlaySound(EventOutcomePlaySound {
+                sound_id: Uuid::nil(),
+            })
+        };
+
+        // Mixed lanes use the lane with the highest priority
+        let sequence = EventOutcome::Sequence(EventOutcomeSequence {
+            steps: [hotkey(), sound()]
+                .into_iter()
+                .map(|outcome| EventOutcomeSequenceStep { delay: 0, outcome })
+                .collect(),
+        });
+        assert_eq!(OutcomeLane::from_outcome(&sequence), OutcomeLane::Sound);
+
+        let random = EventOutcome::Random(EventOutcomeRandom {
+            choices: vec![EventOutcomeRandomChoice {
+                weight: 1,
+                outcome: hotkey(),
+            }],
+        });
+        assert_eq!(OutcomeLane::from_outcome(&random), OutcomeLane::Other);
+    }
+}
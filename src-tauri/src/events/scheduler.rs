@@ -1,36 +1,38 @@
 use std::{
-    collections::BinaryHeap, future::Future, pin::Pin, sync::Arc, task::Poll, time::Duration,
+    collections::{BinaryHeap, HashMap},
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::Poll,
 };
 
 use anyhow::Context;
-use chrono::Local;
+use chrono::{DateTime, Datelike, Days, Local, NaiveDate, TimeDelta, TimeZone, Utc};
 use futures::future::BoxFuture;
-use log::error;
-use sea_orm::DatabaseConnection;
+use log::{debug, error, warn};
+use rand::Rng;
 use tokio::{
-    sync::{broadcast, mpsc},
+    sync::mpsc,
     time::{sleep_until, Instant},
 };
+use uuid::Uuid;
 
-use crate::{
-    database::entity::{
-        events::{EventTrigger, EventTriggerType},
-        EventModel,
-    },
-    script::runtime::ScriptExecutorHandle,
-    twitch::manager::TwitchManager,
+use crate::database::entity::{
+    events::{EventTrigger, EventTriggerType, TimerWindow},
+    EventModel,
 };
 
 use super::{
     matching::{EventData, EventInputData},
-    processing::execute_event,
-    EventMessage,
+    processing::{execute_event, EventProcessingContext, EventRun},
 };
 
 pub struct ScheduledEvent {
     pub event: EventModel,
     /// Next instance the
     pub next_run: Instant,
+    /// Next time the event will run
+    pub next_run_time: DateTime<Utc>,
 }
 
 impl Eq for ScheduledEvent {}
@@ -55,26 +57,35 @@ impl Ord for ScheduledEvent {
     }
 }
 
+/// Shared map of the next run time for each scheduled event
+type SharedNextRuns = Arc<Mutex<HashMap<Uuid, DateTime<Utc>>>>;
+
 #[derive(Clone)]
-pub struct SchedulerHandle(mpsc::Sender<Vec<EventModel>>);
+pub struct SchedulerHandle {
+    tx: mpsc::Sender<Vec<EventModel>>,
+    next_runs: SharedNextRuns,
+}
 
 impl SchedulerHandle {
     pub async fn update_events(&self, events: Vec<EventModel>) -> anyhow::Result<()> {
-        self.0.send(events).await.context("failed to send event")
+        self.tx.send(events).await.context("failed to send event")
+    }
+
+    /// Get the next time the event with the provided `event_id` is
+    /// scheduled to run
+    pub fn get_next_run(&self, event_id: Uuid) -> Option<DateTime<Utc>> {
+        let next_runs = self.next_runs.lock().ok()?;
+        next_runs.get(&event_id).copied()
     }
 }
 
-pub fn create_scheduler(
-    db: DatabaseConnection,
-    twitch_manager: Arc<TwitchManager>,
-    script_handle: ScriptExecutorHandle,
-    event_sender: broadcast::Sender<EventMessage>,
-) -> SchedulerHandle {
+pub fn create_scheduler(ctx: EventProcessingContext) -> SchedulerHandle {
     let (tx, rx) = mpsc::channel(5);
+    let next_runs = SharedNextRuns::default();
 
     // Load the initial events data
     tauri::async_runtime::spawn({
-        let db = db.clone();
+        let db = ctx.db.clone();
         let tx = tx.clone();
 
         async move {
@@ -91,13 +102,11 @@ pub fn create_scheduler(
         rx,
         events: BinaryHeap::new(),
         current_sleep: None,
-        db,
-        twitch_manager,
-        script_handle,
-        event_sender,
+        ctx,
+        next_runs: next_runs.clone(),
     });
 
-    SchedulerHandle(tx)
+    SchedulerHandle { tx, next_runs }
 }
 
 struct SchedulerEventLoop {
@@ -111,39 +120,80 @@ struct SchedulerEventLoop {
     /// Current sleep future
     current_sleep: Option<BoxFuture<'static, ()>>,
 
-    db: DatabaseConnection,
-    twitch_manager: Arc<TwitchManager>,
-    script_handle: ScriptExecutorHandle,
-    event_sender: broadcast::Sender<EventMessage>,
+    /// Shared state for executing events
+    ctx: EventProcessingContext,
+
+    /// Next run times shared with the [SchedulerHandle]
+    next_runs: SharedNextRuns,
 }
 
 impl SchedulerEventLoop {
+    /// Updates the shared next run times from the current events
+    fn update_next_runs(&self) {
+        let mut next_runs = match self.next_runs.lock() {
+            Ok(value) => value,
+            Err(_) => return,
+        };
+
+        next_runs.clear();
+        next_runs.extend(
+            self.events
+                .iter()
+                .map(|event| (event.event.id, event.next_run_time)),
+        );
+    }
+
     fn execute_scheduled_event(&self, event: EventModel) {
-        let db = self.db.clone();
-        let twitch_manager = self.twitch_manager.clone();
-        let script_handle = self.script_handle.clone();
-        let event_sender = self.event_sender.clone();
+        let ctx = self.ctx.clone();
 
         tauri::async_runtime::spawn(async move {
-            let db = db;
-            let twitch_manager = twitch_manager;
-            let script_handle = script_handle;
-            let event_sender = event_sender;
-
-            if let Err(err) = execute_event(
-                &db,
-                &twitch_manager,
-                &script_handle,
-                &event_sender,
+            // Skip all timers while outcomes are paused
+            if ctx.app_data.read().await.paused {
+                debug!("skipping timer event: outcomes paused");
+                return;
+            }
+
+            if let EventTrigger::Timer {
+                only_while_live,
+                min_chat_messages,
+                min_unique_chatters,
+                ..
+            } = &event.trigger
+            {
+                // Skip live only timers while the stream is offline
+                if *only_while_live && !ctx.runtime_app_data.read().await.is_live {
+                    debug!("skipping timer event: stream is not live");
+                    return;
+                }
+
+                // Skip timers until there has been enough chat activity
+                let activity = ctx.chat_activity.get_activity_since_last_run(event.id);
+                if activity.messages < *min_chat_messages
+                    || activity.unique_chatters < *min_unique_chatters
+                {
+                    debug!("skipping timer event: not enough chat activity ({activity:?})");
+                    return;
+                }
+            }
+
+            let event_id = event.id;
+            let result = execute_event(
+                &ctx,
                 event,
                 EventData {
                     user: None,
                     input_data: EventInputData::None,
                 },
             )
-            .await
-            {
-                error!("error while executing event outcome (in timer): {err:?}");
+            .await;
+
+            match result {
+                // Chat activity is only reset once the timer has actually run
+                Ok(EventRun::Completed) => ctx.chat_activity.mark_event_run(event_id),
+                Ok(EventRun::Skipped | EventRun::Cancelled) => {}
+                Err(err) => {
+                    error!("error while executing event outcome (in timer): {err:?}");
+                }
             }
         });
     }
@@ -159,6 +209,8 @@ impl SchedulerEventLoop {
 
             // Clear sleep state
             self.current_sleep = None;
+
+            self.update_next_runs();
         }
 
         if let Some(current_sleep) = self.current_sleep.as_mut() {
@@ -183,6 +235,8 @@ impl SchedulerEventLoop {
                 self.events.push(event);
             }
 
+            self.update_next_runs();
+
             // Emit event
             return Poll::Ready(());
         }
@@ -218,19 +272,209 @@ impl Future for SchedulerEventLoop {
 }
 
 fn create_scheduled_event(event: EventModel) -> Option<ScheduledEvent> {
-    let interval = match &event.trigger {
-        EventTrigger::Timer { interval } => *interval,
+    let (interval, window, jitter) = match &event.trigger {
+        EventTrigger::Timer {
+            interval,
+            window,
+            jitter,
+            ..
+        } => (*interval, window.as_ref(), *jitter),
         _ => return None,
     };
 
-    let next_run = get_next_interval_instant(interval);
-    Some(ScheduledEvent { event, next_run })
-}
+    // Pick the random jitter
+    let jitter = if jitter > 0 {
+        TimeDelta::seconds(rand::thread_rng().gen_range(0..=jitter) as i64)
+    } else {
+        TimeDelta::zero()
+    };
 
-/// Gets the next instant for a fixed interval
-fn get_next_interval_instant(interval: u64) -> Instant {
     let now = Local::now();
-    let seconds_since_epoch = now.timestamp() as u64;
-    let next = (seconds_since_epoch / interval + 1) * interval;
-    Instant::now() + Duration::from_secs(next - seconds_since_epoch)
+    let next_run_time = match get_next_timer_run(interval, window, jitter, now) {
+        Some(value) => value,
+        None => {
+            warn!("timer event {} window never opens, skipping", event.id);
+            return None;
+        }
+    };
+
+    let next_run = Instant::now() + (next_run_time - now).to_std().unwrap_or_default();
+
+    Some(ScheduledEvent {
+        event,
+        next_run,
+        next_run_time: next_run_time.with_timezone(&Utc),
+    })
+}
+
+/// Maximum number of window openings to search through when
+/// finding the next run
+const MAX_WINDOW_SEARCH: usize = 16;
+
+/// Gets the next time a timer should run with the `jitter` applied, only
+/// allowing times within the optional `window` in the timezone of `now`
+fn get_next_timer_run<Tz>(
+    interval: u64,
+    window: Option<&TimerWindow>,
+    jitter: TimeDelta,
+    now: DateTime<Tz>,
+) -> Option<DateTime<Tz>>
+where
+    Tz: TimeZone,
+    Tz::Offset: Copy,
+{
+    let mut next = get_next_interval_time(interval, now);
+
+    let window = match window {
+        Some(value) => value,
+        None => return Some(next + jitter),
+    };
+
+    for _ in 0..MAX_WINDOW_SEARCH {
+        if is_within_window(window, &(next + jitter)) {
+            return Some(next + jitter);
+        }
+
+        // Jitter moved the run outside the window, run without it instead
+        if is_within_window(window, &next) {
+            return Some(next);
+        }
+
+        // Move to the first interval once the window next opens
+        let opening = get_next_window_opening(window, &next)?;
+        next = get_next_interval_time(interval, opening - TimeDelta::seconds(1));
+    }
+
+    None
+}
+
+/// Gets the next time for a fixed interval (Aligned to the unix epoch)
+fn get_next_interval_time<Tz>(interval: u64, now: DateTime<Tz>) -> DateTime<Tz>
+where
+    Tz: TimeZone,
+    Tz::Offset: Copy,
+{
+    let interval = interval.max(1) as i64;
+    let seconds_since_epoch = now.timestamp();
+    let next = (seconds_since_epoch.div_euclid(interval) + 1) * interval;
+    now + TimeDelta::seconds(next - seconds_since_epoch)
+}
+
+/// Checks if the provided `time` is within the timer `window`, the window
+/// is in the timezone of the `time`
+fn is_within_window<Tz: TimeZone>(window: &TimerWindow, time: &DateTime<Tz>) -> bool {
+    let is_day_allowed =
+        |date: NaiveDate| window.days.is_empty() || window.days.contains(&date.weekday());
+
+    let date = time.date_naive();
+    let time = time.time();
+
+    if window.start == window.end {
+        // Window spans the entire day
+        is_day_allowed(date)
+    } else if window.start < window.end {
+        time >= window.start && time < window.end && is_day_allowed(date)
+    } else if time >= window.start {
+        // Window extending past midnight, before midnight
+        is_day_allowed(date)
+    } else if time < window.end {
+        // Window extending past midnight, after midnight the window belongs to the previous day
+        date.pred_opt().is_some_and(is_day_allowed)
+    } else {
+        false
+    }
+}
+
+/// Gets the next time after `after` that the timer `window` opens
+fn get_next_window_opening<Tz: TimeZone>(
+    window: &TimerWindow,
+    after: &DateTime<Tz>,
+) -> Option<DateTime<Tz>> {
+    let timezone = after.timezone();
+
+    (0..=7)
+        .filter_map(|offset| after.date_naive().checked_add_days(Days::new(offset)))
+        .filter(|date| window.days.is_empty() || window.days.contains(&date.weekday()))
+        .filter_map(|date| {
+            timezone
+                .from_local_datetime(&date.and_time(window.start))
+                .earliest()
+        })
+        .find(|opening| opening > after)
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::{DateTime, FixedOffset, NaiveTime, TimeDelta, TimeZone, Utc, Weekday};
+
+    use super::{get_next_timer_run, is_within_window};
+    use crate::database::entity::events::TimerWindow;
+
+    fn window(start: (u32, u32), end: (u32, u32), days: Vec<Weekday>) -> TimerWindow {
+        TimerWindow {
+            start: NaiveTime::from_hms_opt(start.0, start.1, 0).unwrap(),
+            end: NaiveTime::from_hms_opt(end.0, end.1, 0).unwrap(),
+            days,
+        }
+    }
+
+    /// Creates a time in a fixed UTC+10:30 timezone so the results don't
+    /// depend on the timezone of the machine running the tests
+    fn time(day: u32, hour: u32, min: u32) -> DateTime<FixedOffset> {
+        FixedOffset::east_opt(10 * 3600 + 30 * 60)
+            .unwrap()
+            .with_ymd_and_hms(2025, 1, day, hour, min, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_within_window() {
+        let window = window((9, 0), (17, 0), vec![Weekday::Mon]);
+
+        // 2025-01-06 is a monday
+        assert!(is_within_window(&window, &time(6, 12, 0)));
+        assert!(!is_within_window(&window, &time(6, 18, 0)));
+        assert!(!is_within_window(&window, &time(7, 12, 0)));
+
+        // Window is checked in the timezone of the time, 12:00 UTC+10:30
+        // is still before the window on monday in UTC
+        assert!(!is_within_window(
+            &window,
+            &time(6, 12, 0).with_timezone(&Utc)
+        ));
+    }
+
+    #[test]
+    fn test_within_overnight_window() {
+        let window = window((22, 0), (2, 0), vec![Weekday::Fri]);
+
+        // 2025-01-10 is a friday
+        assert!(is_within_window(&window, &time(10, 23, 0)));
+        assert!(is_within_window(&window, &time(11, 1, 0)));
+        assert!(!is_within_window(&window, &time(11, 23, 0)));
+    }
+
+    #[test]
+    fn test_next_run_waits_for_window() {
+        let window = window((9, 0), (17, 0), vec![Weekday::Mon]);
+
+        // Saturday evening should wait until monday morning
+        let now = time(4, 18, 0);
+        let next = get_next_timer_run(60 * 15, Some(&window), TimeDelta::zero(), now).unwrap();
+
+        assert_eq!(next, time(6, 9, 0));
+    }
+
+    #[test]
+    fn test_next_run_jitter_within_window() {
+        let window = window((9, 0), (17, 0), vec![Weekday::Mon]);
+        let now = time(6, 16, 40);
+
+        let next = get_next_timer_run(60 * 15, Some(&window), TimeDelta::minutes(5), now).unwrap();
+        assert_eq!(next, time(6, 16, 50));
+
+        // Jitter past the end of the window is not applied
+        let next = get_next_timer_run(60 * 15, Some(&window), TimeDelta::minutes(20), now).unwrap();
+        assert_eq!(next, time(6, 16, 45));
+    }
 }
@@ -0,0 +1,175 @@
+//! # Seen Viewers
+//!
+//! Tracks the viewers seen chatting during the current stream, used to
+//! determine first messages without storing every chat message
+//!
+//! Viewers are only known once they have been seen by the app, the first
+//! stream after tracking starts is used to seed the seen viewers so viewers
+//! that chatted before the app was installed are not treated as new
+
+use std::{
+    collections::HashSet,
+    sync::{Arc, Mutex},
+};
+
+use log::error;
+use sea_orm::{prelude::DateTimeUtc, DatabaseConnection};
+use twitch_api::types::{StreamId, UserId};
+
+use crate::database::entity::SeenViewerModel;
+
+/// Shared tracker for viewers seen during the current stream
+#[derive(Clone, Default)]
+pub struct SeenViewerTracker {
+    inner: Arc<Mutex<SeenViewerState>>,
+}
+
+#[derive(Default)]
+struct SeenViewerState {
+    /// ID of the current stream, [None] while offline
+    stream_id: Option<StreamId>,
+    /// Viewers already recorded during the current stream
+    seen: HashSet<UserId>,
+    /// Whether viewers have been seeded from a previous stream
+    seeded: bool,
+}
+
+/// Whether a chat message was the first message from the viewer
+#[derive(Debug, Default, Clone, Copy)]
+pub struct FirstMessage {
+    /// First message from the viewer seen by the app, always false
+    /// while the seen viewers are being seeded
+    pub first_ever: bool,
+    /// First message from the viewer during the current stream
+    pub first_stream: bool,
+}
+
+impl SeenViewerTracker {
+    /// Sets the current stream, viewers seen during a previous
+    /// stream will be recorded again
+    pub fn set_stream(&self, stream_id: Option<StreamId>) {
+        let state = &mut *self.inner.lock().expect("seen viewers lock poisoned");
+        if state.stream_id != stream_id {
+            state.stream_id = stream_id;
+            state.seen.clear();
+        }
+    }
+
+    /// Marks the viewer as seen, returns the current stream ID when the
+    /// viewer had not been seen yet during the current stream
+    fn mark_seen(&self, user_id: &UserId) -> Option<Option<StreamId>> {
+        let state = &mut *self.inner.lock().expect("seen viewers lock poisoned");
+        state
+            .seen
+            .insert(user_id.clone())
+            .then(|| state.stream_id.clone())
+    }
+
+    /// Checks if the seen viewers have been seeded by a stream other than
+    /// the current stream, viewers that are new while seeding may have
+    /// chatted before tracking started
+    async fn is_seeded(&self, db: &DatabaseConnection, stream_id: Option<&StreamId>) -> bool {
+        if self
+            .inner
+            .lock()
+            .expect("seen viewers lock poisoned")
+            .seeded
+        {
+            return true;
+        }
+
+        let seeded = match SeenViewerModel::has_viewer_from_other_stream(
+            db,
+            stream_id.map(|value| value.as_str()),
+        )
+        .await
+        {
+            Ok(value) => value,
+            Err(err) => {
+                error!("failed to check seen viewers: {:?}", err);
+                return false;
+            }
+        };
+
+        if seeded {
+            self.inner
+                .lock()
+                .expect("seen viewers lock poisoned")
+                .seeded = true;
+        }
+
+        seeded
+    }
+
+    /// Records a chat message from the viewer with the provided `user_id`,
+    /// the seen viewers table is only updated for the first message from
+    /// the viewer during each stream
+    pub async fn record_message(
+        &self,
+        db: &DatabaseConnection,
+        user_id: &UserId,
+        current_time: DateTimeUtc,
+    ) -> FirstMessage {
+        // Viewer already chatted during this stream
+        let stream_id = match self.mark_seen(user_id) {
+            Some(value) => value,
+            None => return FirstMessage::default(),
+        };
+
+        let previous = match SeenViewerModel::record_message(
+            db,
+            user_id.as_str(),
+            stream_id.as_ref().map(|value| value.as_str()),
+            current_time,
+        )
+        .await
+        {
+            Ok(value) => value,
+            Err(err) => {
+                error!("failed to record seen viewer: {:?}", err);
+                return FirstMessage::default();
+            }
+        };
+
+        // New viewers are only first seen once seeding has finished
+        let first_ever = previous.is_none() && self.is_seeded(db, stream_id.as_ref()).await;
+
+        // Stream messages can only be tracked while live
+        let first_stream = stream_id.is_some_and(|stream_id| {
+            previous.as_ref().is_none_or(|previous| {
+                previous.last_stream_id.as_deref() != Some(stream_id.as_str())
+            })
+        });
+
+        FirstMessage {
+            first_ever,
+            first_stream,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use twitch_api::types::{StreamId, UserId};
+
+    use super::SeenViewerTracker;
+
+    #[test]
+    fn test_mark_seen_per_stream() {
+        let tracker = SeenViewerTracker::default();
+        let user = UserId::from_static("1");
+        let stream = StreamId::from_static("100");
+
+        tracker.set_stream(Some(stream.clone()));
+        assert_eq!(tracker.mark_seen(&user), Some(Some(stream.clone())));
+        assert_eq!(tracker.mark_seen(&user), None);
+
+        // Same stream keeps the viewers seen
+        tracker.set_stream(Some(stream));
+        assert_eq!(tracker.mark_seen(&user), None);
+
+        // Viewers are seen again once the stream changes
+        tracker.set_stream(None);
+        assert_eq!(tracker.mark_seen(&user), Some(None));
+    }
+}
@@ -0,0 +1,592 @@
+//! # Template
+//!
+//! Templates for chat messages sent by event outcomes, commands and
+//! feedback replies
+//!
+//! Variables take the form `$(name args...)` and can provide a fallback
+//! used when the variable has no value using `$(name args...|fallback)`
+
+use anyhow::{anyhow, bail};
+use log::error;
+use rand::Rng;
+use sea_orm::DatabaseConnection;
+use twitch_api::types::SubscriptionTier;
+
+use crate::{database::entity::KeyValueModel, twitch::manager::TwitchEventUser};
+
+use super::matching::EventInputData;
+
+/// Set of variables available to a template
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TemplateVariables {
+    /// Chat messages sent by event outcomes and commands
+    #[default]
+    Message,
+    /// Feedback replies sent when an event or command is skipped
+    Feedback,
+}
+
+/// Context available to variables when rendering a template
+#[derive(Default)]
+pub struct TemplateContext<'a> {
+    /// Variables available to the template
+    pub variables: TemplateVariables,
+    /// User who triggered the event or command
+    pub user: Option<&'a TwitchEventUser>,
+    /// Input data from the event
+    pub input_data: Option<&'a EventInputData>,
+    /// Arguments provided to the command
+    pub args: &'a [String],
+    /// Remaining cooldown for feedback replies
+    pub remaining: Option<String>,
+    /// Required role for feedback replies
+    pub role: Option<&'a str>,
+}
+
+/// Variable within a template
+#[derive(Debug, PartialEq)]
+struct TemplateVariable<'a> {
+    /// Name of the variable
+    name: &'a str,
+    /// Arguments provided to the variable
+    args: Vec<&'a str>,
+    /// Value to use when the variable has no value
+    fallback: Option<&'a str>,
+}
+
+/// Part of a parsed template
+#[derive(Debug, PartialEq)]
+enum TemplatePart<'a> {
+    /// Plain text
+    Text(&'a str),
+    /// Variable to replace, includes the original text of the variable
+    Variable(TemplateVariable<'a>, &'a str),
+}
+
+/// Variables that take their value from the event input data
+const INPUT_VARIABLES: &[&str] = &[
+    "userInput",
+    "rewardName",
+    "rewardCost",
+    "bits",
+    "tier",
+    "isGift",
+    "total",
+    "cumulativeTotal",
+    "months",
+    "streakMonths",
+    "durationMonths",
+    "viewers",
+    "duration",
+    "level",
+    "title",
+    "choices",
+    "winner",
+    "winnerVotes",
+    "isTie",
+    "outcomes",
+    "winnerUsers",
+    "winnerPoints",
+    "canceled",
+    "topContributor",
+    "topContributors",
+];
+
+/// Parses the parts of a template, the position of any unclosed
+/// variable is returned as the error
+fn parse_template(template: &str) -> Result<Vec<TemplatePart<'_>>, usize> {
+    let mut parts = Vec::new();
+    let mut rest = template;
+
+    while let Some(start) = rest.find("$(") {
+        if start > 0 {
+            parts.push(TemplatePart::Text(&rest[..start]));
+        }
+
+        let inner = &rest[start + 2..];
+        let end = inner
+            .find(')')
+            .ok_or_else(|| template.len() - rest.len() + start)?;
+
+        let raw = &rest[start..start + 2 + end + 1];
+        let (expr, fallback) = match inner[..end].split_once('|') {
+            Some((expr, fallback)) => (expr, Some(fallback)),
+            None => (&inner[..end], None),
+        };
+
+        let mut words = expr.split_whitespace();
+        let name = words.next().unwrap_or_default();
+        let args = words.collect();
+
+        parts.push(TemplatePart::Variable(
+            TemplateVariable {
+                name,
+                args,
+                fallback,
+            },
+            raw,
+        ));
+
+        rest = &inner[end + 1..];
+    }
+
+    if !rest.is_empty() {
+        parts.push(TemplatePart::Text(rest));
+    }
+
+    Ok(parts)
+}
+
+/// Validates the variables used within a template, unknown variables
+/// and invalid arguments are reported as errors
+pub fn validate_template(template: &str, variables: TemplateVariables) -> anyhow::Result<()> {
+    let parts = parse_template(template)
+        .map_err(|index| anyhow!("unclosed template variable at position {index}"))?;
+
+    for part in parts {
+        if let TemplatePart::Variable(variable, raw) = part {
+            validate_variable(&variable, variables).map_err(|err| anyhow!("{raw}: {err}"))?;
+        }
+    }
+
+    Ok(())
+}
+
+fn validate_variable(
+    variable: &TemplateVariable<'_>,
+    variables: TemplateVariables,
+) -> anyhow::Result<()> {
+    let TemplateVariable { name, args, .. } = variable;
+
+    if variables == TemplateVariables::Feedback {
+        return match *name {
+            "user" | "remaining" | "role" if args.is_empty() => Ok(()),
+            "user" | "remaining" | "role" => bail!("expected 0 argument(s) but got {}", args.len()),
+            "" => bail!("missing variable name"),
+            _ => bail!("unknown feedback variable"),
+        };
+    }
+
+    let expected_args = match *name {
+        "user" | "touser" | "args" => 0,
+        "arg" => {
+            match args.first().map(|value| value.parse::<usize>()) {
+                Some(Ok(index)) if index > 0 => {}
+                _ => bail!("expected argument number starting from 1"),
+            }
+            1
+        }
+        "random" => {
+            let (min, max) = match args.as_slice() {
+                [min, max] => (min.parse::<i64>(), max.parse::<i64>()),
+                _ => bail!("expected minimum and maximum values"),
+            };
+
+            match (min, max) {
+                (Ok(min), Ok(max)) if min <= max => {}
+                (Ok(_), Ok(_)) => bail!("minimum must not be greater than maximum"),
+                _ => bail!("minimum and maximum must be whole numbers"),
+            }
+            2
+        }
+        "counter" => 1,
+        name if name.starts_with("capture.") && name.len() > "capture.".len() => 0,
+        name if INPUT_VARIABLES.contains(&name) => 0,
+        "" => bail!("missing variable name"),
+        _ => bail!("unknown template variable"),
+    };
+
+    if args.len() != expected_args {
+        bail!(
+            "expected {expected_args} argument(s) but got {}",
+            args.len()
+        );
+    }
+
+    Ok(())
+}
+
+/// Renders the template replacing any variables with their values,
+/// unknown variables are left as is
+pub async fn render_template(
+    db: &DatabaseConnection,
+    template: &str,
+    ctx: &TemplateContext<'_>,
+) -> String {
+    let parts = match parse_template(template) {
+        Ok(value) => value,
+        Err(_) => return template.to_string(),
+    };
+
+    let mut output = String::with_capacity(template.len());
+
+    for part in parts {
+        match part {
+            TemplatePart::Text(text) => output.push_str(text),
+            TemplatePart::Variable(variable, raw) => {
+                if validate_variable(&variable, ctx.variables).is_err() {
+                    output.push_str(raw);
+                    continue;
+                }
+
+                let value = get_variable_value(db, &variable, ctx).await;
+                match (value, variable.fallback) {
+                    (Some(value), _) => output.push_str(&value),
+                    (None, Some(fallback)) => output.push_str(fallback),
+                    // Anonymous events have no user
+                    (None, None) if variable.name == "user" => output.push_str("Anonymous"),
+                    (None, None) => {}
+                }
+            }
+        }
+    }
+
+    output
+}
+
+/// Get the value for a variable, [None] when the variable has no
+/// value in the current context
+async fn get_variable_value(
+    db: &DatabaseConnection,
+    variable: &TemplateVariable<'_>,
+    ctx: &TemplateContext<'_>,
+) -> Option<String> {
+    if ctx.variables == TemplateVariables::Feedback {
+        return match variable.name {
+            "user" => ctx.user.map(|user| user.name.to_string()),
+            "remaining" => ctx.remaining.clone(),
+            "role" => ctx.role.map(str::to_string),
+            _ => None,
+        };
+    }
+
+    match variable.name {
+        "user" => ctx.user.map(|user| user.name.to_string()),
+        "touser" => ctx.args.first().cloned(),
+        "args" => (!ctx.args.is_empty()).then(|| ctx.args.join(" ")),
+        "arg" => {
+            let index: usize = variable.args.first()?.parse().ok()?;
+            ctx.args.get(index.checked_sub(1)?).cloned()
+        }
+        "random" => {
+            let min: i64 = variable.args.first()?.parse().ok()?;
+            let max: i64 = variable.args.get(1)?.parse().ok()?;
+            Some(rand::thread_rng().gen_range(min..=max).to_string())
+        }
+        "counter" => {
+            let key = variable.args.first()?;
+            match KeyValueModel::get_by_key(db, key).await {
+                Ok(value) => value.map(|value| value.value),
+                Err(err) => {
+                    error!("failed to get counter for template: {err:?}");
+                    None
+                }
+            }
+        }
+        name => get_input_value(name, ctx.input_data?),
+    }
+}
+
+/// Get the value of a variable from the event input data
+fn get_input_value(name: &str, input_data: &EventInputData) -> Option<String> {
+    if let Some(capture) = name.strip_prefix("capture.") {
+        let EventInputData::Chat { captures, .. } = input_data else {
+            return None;
+        };
+
+        return match capture.parse::<usize>() {
+            Ok(index) => captures.groups.get(index).cloned().flatten(),
+            Err(_) => captures.named.get(capture).cloned(),
+        };
+    }
+
+    let value = match (name, input_data) {
+        ("userInput", EventInputData::Redeem { user_input, .. }) => user_input.clone(),
+        ("userInput", EventInputData::Bits { message, .. })
+        | ("userInput", EventInputData::ReSubscription { message, .. })
+        | ("userInput", EventInputData::Chat { message, .. }) => message.clone(),
+        ("rewardName", EventInputData::Redeem { reward_name, .. }) => reward_name.clone(),
+        ("rewardCost", EventInputData::Redeem { cost, .. }) => cost.to_string(),
+        ("bits", EventInputData::Bits { bits, .. }) => bits.to_string(),
+        ("bits", EventInputData::Chat { cheer, .. }) => cheer.as_ref()?.to_string(),
+        ("tier", EventInputData::Subscription { tier, .. })
+        | ("tier", EventInputData::GiftedSubscription { tier, .. })
+        | ("tier", EventInputData::ReSubscription { tier, .. }) => get_tier_name(tier)?,
+        ("isGift", EventInputData::Subscription { is_gift, .. }) => is_gift.to_string(),
+        ("total", EventInputData::GiftedSubscription { total, .. })
+        | ("total", EventInputData::HypeTrain { total, .. }) => total.to_string(),
+        (
+            "cumulativeTotal",
+            EventInputData::GiftedSubscription {
+                cumulative_total, ..
+            },
+        ) => cumulative_total.as_ref()?.to_string(),
+        (
+            "months",
+            EventInputData::ReSubscription {
+                cumulative_months, ..
+            },
+        ) => cumulative_months.to_string(),
+        ("streakMonths", EventInputData::ReSubscription { streak_months, .. }) => {
+            streak_months.as_ref()?.to_string()
+        }
+        (
+            "durationMonths",
+            EventInputData::ReSubscription {
+                duration_months, ..
+            },
+        ) => duration_months.to_string(),
+        ("viewers", EventInputData::Raid { viewers }) => viewers.to_string(),
+        ("viewers", EventInputData::ShoutoutReceive { viewer_count }) => viewer_count.to_string(),
+        ("duration", EventInputData::AdBreakBegin { duration_seconds }) => {
+            duration_seconds.to_string()
+        }
+        ("level", EventInputData::HypeTrain { level, .. }) => level.to_string(),
+        ("title", EventInputData::Poll { title, .. })
+        | ("title", EventInputData::Prediction { title, .. }) => title.clone(),
+        ("choices", EventInputData::Poll { choices, .. }) => {
+            join_names(choices.iter().map(|choice| choice.title.as_str()))
+        }
+        (
+            "winner",
+            EventInputData::Poll {
+                winning_choices, ..
+            },
+        ) if !winning_choices.is_empty() => {
+            join_names(winning_choices.iter().map(|choice| choice.title.as_str()))
+        }
+        (
+            "winnerVotes",
+            EventInputData::Poll {
+                winning_choices, ..
+            },
+        ) => winning_choices.first()?.votes.to_string(),
+        (
+            "isTie",
+            EventInputData::Poll {
+                winning_choices, ..
+            },
+        ) => (winning_choices.len() > 1).to_string(),
+        ("outcomes", EventInputData::Prediction { outcomes, .. }) => {
+            join_names(outcomes.iter().map(|outcome| outcome.title.as_str()))
+        }
+        (
+            "winner",
+            EventInputData::Prediction {
+                winning_outcome, ..
+            },
+        ) => winning_outcome.as_ref()?.title.clone(),
+        (
+            "winnerUsers",
+            EventInputData::Prediction {
+                winning_outcome, ..
+            },
+        ) => winning_outcome.as_ref()?.users.to_string(),
+        (
+            "winnerPoints",
+            EventInputData::Prediction {
+                winning_outcome, ..
+            },
+        ) => winning_outcome.as_ref()?.channel_points.to_string(),
+        ("canceled", EventInputData::Prediction { canceled, .. }) => canceled.to_string(),
+        (
+            "topContributor",
+            EventInputData::HypeTrain {
+                top_contributions, ..
+            },
+        ) => top_contributions.first()?.user.display_name.to_string(),
+        (
+            "topContributors",
+            EventInputData::HypeTrain {
+                top_contributions, ..
+            },
+        ) if !top_contributions.is_empty() => join_names(
+            top_contributions
+                .iter()
+                .map(|contribution| contribution.user.display_name.as_str()),
+        ),
+        _ => return None,
+    };
+
+    Some(value)
+}
+
+/// Joins names into a comma separated list for use in chat messages
+fn join_names<'a>(names: impl Iterator<Item = &'a str>) -> String {
+    names.collect::<Vec<_>>().join(", ")
+}
+
+/// Get a display name for a subscription tier for use in chat messages
+fn get_tier_name(tier: &SubscriptionTier) -> Option<String> {
+    let name = match tier {
+        SubscriptionTier::Tier1 => "1",
+        SubscriptionTier::Tier2 => "2",
+        SubscriptionTier::Tier3 => "3",
+        SubscriptionTier::Prime => "Prime",
+        _ => return None,
+    };
+
+    Some(name.to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use twitch_api::{
+        eventsub::channel::hypetrain::ContributionType,
+        types::{DisplayName, UserId, UserName},
+    };
+
+    use super::{
+        get_input_value, parse_template, validate_template, EventInputData, TemplateVariables,
+    };
+    use crate::{
+        events::matching::{HypeTrainContribution, PollChoice, PredictionOutcome},
+        twitch::manager::TwitchEventUser,
+    };
+
+    #[test]
+    fn test_parse_template() {
+        let parts = parse_template("Hi $(user|friend), roll: $(random 1 100)").unwrap();
+        assert_eq!(parts.len(), 4);
+        assert!(parse_template("Hi $(user").is_err());
+    }
+
+    #[test]
+    fn test_validate_template() {
+        let validate = |template| validate_template(template, TemplateVariables::Message);
+
+        assert!(validate("$(user) gifted $(total|some) subs").is_ok());
+        assert!(validate("$(arg 2) $(random 1 100) $(counter deaths)").is_ok());
+        assert!(validate("$(capture.name) $(capture.0)").is_ok());
+        assert!(validate("$(unknown)").is_err());
+        assert!(validate("$(arg 0)").is_err());
+        assert!(validate("$(random 100 1)").is_err());
+        assert!(validate("$(user").is_err());
+        assert!(validate("$(remaining)").is_err());
+    }
+
+    #[test]
+    fn test_validate_feedback_template() {
+        let validate = |template| validate_template(template, TemplateVariables::Feedback);
+
+        assert!(validate("@$(user) wait $(remaining|a bit)").is_ok());
+        assert!(validate("@$(user) you must be a $(role)").is_ok());
+        assert!(validate("$(role mod)").is_err());
+        assert!(validate("$(counter deaths)").is_err());
+        assert!(validate("$(remaining").is_err());
+    }
+
+    #[test]
+    fn test_input_value() {
+        let input = EventInputData::ReSubscription {
+            cumulative_months: 12,
+            duration_months: 1,
+            message: "hello".to_string(),
+            streak_months: None,
+            tier: twitch_api::types::SubscriptionTier::Tier2,
+        };
+
+        assert_eq!(get_input_value("months", &input).as_deref(), Some("12"));
+        assert_eq!(get_input_value("tier", &input).as_deref(), Some("2"));
+        assert_eq!(get_input_value("streakMonths", &input), None);
+        assert_eq!(get_input_value("bits", &input), None);
+    }
+
+    #[test]
+    fn test_poll_input_value() {
+        let choice = |title: &str, votes: i64| PollChoice {
+            id: title.to_string(),
+            title: title.to_string(),
+            votes,
+        };
+
+        let input = EventInputData::Poll {
+            title: "Best snack".to_string(),
+            choices: vec![choice("Chips", 4), choice("Cookies", 4), choice("Fruit", 1)],
+            winning_choice: None,
+            winning_choices: vec![choice("Chips", 4), choice("Cookies", 4)],
+        };
+
+        assert_eq!(
+            get_input_value("choices", &input).as_deref(),
+            Some("Chips, Cookies, Fruit")
+        );
+        assert_eq!(
+            get_input_value("winner", &input).as_deref(),
+            Some("Chips, Cookies")
+        );
+        assert_eq!(get_input_value("winnerVotes", &input).as_deref(), Some("4"));
+        assert_eq!(get_input_value("isTie", &input).as_deref(), Some("true"));
+    }
+
+    #[test]
+    fn test_prediction_input_value() {
+        let outcome = PredictionOutcome {
+            id: "1".to_string(),
+            title: "Win".to_string(),
+            users: 10,
+            channel_points: 5000,
+        };
+
+        let input = EventInputData::Prediction {
+            title: "Will we win?".to_string(),
+            outcomes: vec![outcome.clone()],
+            winning_outcome: Some(outcome),
+            canceled: false,
+        };
+
+        assert_eq!(get_input_value("outcomes", &input).as_deref(), Some("Win"));
+        assert_eq!(get_input_value("winner", &input).as_deref(), Some("Win"));
+        assert_eq!(
+            get_input_value("winnerUsers", &input).as_deref(),
+            Some("10")
+        );
+        assert_eq!(
+            get_input_value("winnerPoints", &input).as_deref(),
+            Some("5000")
+        );
+        assert_eq!(
+            get_input_value("canceled", &input).as_deref(),
+            Some("false")
+        );
+    }
+
+    #[test]
+    fn test_hype_train_input_value() {
+        let contribution = |name: &'static str| HypeTrainContribution {
+            user: TwitchEventUser {
+                id: UserId::from_static("1"),
+                name: UserName::from_static(name),
+                display_name: DisplayName::from_static(name),
+            },
+            ty: ContributionType::Bits,
+            total: 100,
+        };
+
+        let mut input = EventInputData::HypeTrain {
+            level: 2,
+            total: 500,
+            top_contributions: vec![contribution("alice"), contribution("bob")],
+        };
+
+        assert_eq!(get_input_value("level", &input).as_deref(), Some("2"));
+        assert_eq!(
+            get_input_value("topContributor", &input).as_deref(),
+            Some("alice")
+        );
+        assert_eq!(
+            get_input_value("topContributors", &input).as_deref(),
+            Some("alice, bob")
+        );
+
+        if let EventInputData::HypeTrain {
+            top_contributions, ..
+        } = &mut input
+        {
+            top_contributions.clear();
+        }
+
+        assert_eq!(get_input_value("topContributor", &input), None);
+        assert_eq!(get_input_value("topContributors", &input), None);
+    }
+}
@@ -25,8 +25,9 @@ use tauri::{path::BaseDirectory, AppHandle, Manager};
 pub async fn get_app_data(Extension(app_data): Extension<AppDataStore>) -> Json<AppData> {
     let mut data = app_data.read().await.clone();
 
-    // Hide twitch access token from frontend
+    // Hide twitch access tokens from frontend
     data.twitch_config.access_token = None;
+    data.twitch_config.bot_access_token = None;
 
     Json(data)
 }
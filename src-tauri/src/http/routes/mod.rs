@@ -13,6 +13,11 @@ pub fn router() -> Router {
     Router::new()
         .route("/oauth", get(oauth::handle_oauth))
         .route("/oauth/complete", post(oauth::handle_oauth_complete))
+        .route("/oauth/bot", get(oauth::handle_oauth))
+        .route(
+            "/oauth/bot/complete",
+            post(oauth::handle_bot_oauth_complete),
+        )
         .route("/events", get(events::handle_sse))
         .route(
             "/calibration",
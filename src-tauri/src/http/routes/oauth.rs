@@ -17,6 +17,7 @@ use crate::{
 static OAUTH_RESPONSE_PAGE: &str = include_str!("../resources/twitch-oauth-response.html");
 
 /// GET /oauth
+/// GET /oauth/bot
 ///
 /// Handles an OAuth response from twitch
 ///
@@ -61,3 +62,33 @@ pub async fn handle_oauth_complete(
 
     Ok(Json(()))
 }
+
+/// POST /oauth/bot/complete
+///
+/// Handles the completion of OAuth logging into the bot account storing
+/// the access token and authorized scopes
+pub async fn handle_bot_oauth_complete(
+    Extension(app_data): Extension<AppDataStore>,
+    Extension(twitch_manager): Extension<Arc<TwitchManager>>,
+    Json(req): Json<OAuthComplete>,
+) -> HttpResult<()> {
+    let token =
+        UserToken::from_existing(&twitch_manager.helix_client, req.access_token, None, None)
+            .await
+            .context("failed to create user token")?;
+
+    let access_token = token.access_token.clone();
+    let scopes = req.scopes;
+
+    twitch_manager.set_bot_authenticated(token).await;
+
+    app_data
+        .write(|app_data| {
+            app_data.twitch_config.bot_access_token = Some(access_token);
+            app_data.twitch_config.bot_scopes = Some(scopes);
+        })
+        .await
+        .context("saving app data")?;
+
+    Ok(Json(()))
+}
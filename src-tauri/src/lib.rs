@@ -1,15 +1,28 @@
 use anyhow::Context;
-use constants::TWITCH_REQUIRED_SCOPES;
+use chrono::Utc;
+use constants::{TWITCH_BOT_REQUIRED_SCOPES, TWITCH_REQUIRED_SCOPES};
 use database::clean_old_data;
 use events::{
-    create_event_channel, processing::process_twitch_events, scheduler::create_scheduler,
+    chat_activity::ChatActivityTracker,
+    cooldowns::CooldownTracker,
+    create_event_channel,
+    feedback::FeedbackLimiter,
+    group_cache::GroupCache,
+    processing::{process_twitch_events, EventProcessingContext},
+    queue::OutcomeQueue,
+    scheduler::create_scheduler,
+    seen_viewers::SeenViewerTracker,
 };
 use log::{error, info};
 use script::{events::ScriptEventActor, runtime::create_script_executor};
-use state::{app_data::AppDataStore, runtime_app_data::RuntimeAppDataStore};
+use state::{
+    app_data::{AppDataStore, TwitchConfig},
+    runtime_app_data::RuntimeAppDataStore,
+};
 use std::sync::Arc;
 use tauri::Manager;
 use twitch::manager::TwitchManager;
+use twitch_api::{helix::Scope, twitch_oauth2::AccessToken};
 
 mod commands;
 mod constants;
@@ -52,16 +65,47 @@ pub fn run() {
             let db = tauri::async_runtime::block_on(database::connect_database(&db_file))
                 .expect("failed to load database");
 
-            let (twitch_manager, twitch_event_rx) = TwitchManager::new(handle.clone());
-            let (event_tx, event_rx) = create_event_channel();
-
             let app_data = tauri::async_runtime::block_on(AppDataStore::load(app_data_file))
                 .expect("failed to load app data");
 
             let runtime_app_data = RuntimeAppDataStore::new(handle.clone());
 
+            let (twitch_manager, twitch_event_rx) =
+                TwitchManager::new(handle.clone(), app_data.clone(), runtime_app_data.clone());
+            let (event_tx, event_rx) = create_event_channel();
+
+            // Restore the paused state from the previous run
+            {
+                let paused = app_data.blocking_read().paused;
+                tauri::async_runtime::block_on(
+                    runtime_app_data.write(|runtime_app_data| runtime_app_data.paused = paused),
+                );
+            }
+
             let script_handle = create_script_executor();
 
+            let cooldowns = tauri::async_runtime::block_on(CooldownTracker::load(&db, Utc::now()))
+                .unwrap_or_else(|err| {
+                    error!("failed to load cooldowns: {err:?}");
+                    CooldownTracker::default()
+                });
+
+            // Shared state for processing events
+            let event_ctx = EventProcessingContext {
+                db: db.clone(),
+                twitch_manager: twitch_manager.clone(),
+                script_handle: script_handle.clone(),
+                event_sender: event_tx.clone(),
+                app_data: app_data.clone(),
+                runtime_app_data: runtime_app_data.clone(),
+                chat_activity: ChatActivityTracker::default(),
+                seen_viewers: SeenViewerTracker::default(),
+                cooldowns,
+                groups: GroupCache::default(),
+                feedback: FeedbackLimiter::default(),
+                outcome_queue: OutcomeQueue::new(runtime_app_data.clone()),
+            };
+
             // Add auto updater plugin if auto updating is allowed
             {
                 let app_data = app_data.blocking_read();
@@ -74,12 +118,7 @@ pub fn run() {
             tauri::async_runtime::spawn(clean_old_data(db.clone(), app_data.clone()));
 
             // Create background event scheduler
-            let scheduler_handle = create_scheduler(
-                db.clone(),
-                twitch_manager.clone(),
-                script_handle.clone(),
-                event_tx.clone(),
-            );
+            let scheduler_handle = create_scheduler(event_ctx.clone());
 
             // Provide app data and runtime app data stores
             app.manage(app_data.clone());
@@ -88,6 +127,15 @@ pub fn run() {
             // Provide access to the scheduler
             app.manage(scheduler_handle);
 
+            // Provide access to the outcome queue
+            app.manage(event_ctx.outcome_queue.clone());
+
+            // Provide access to the group cache
+            app.manage(event_ctx.groups.clone());
+
+            // Provide access to the cooldown tracker
+            app.manage(event_ctx.cooldowns.clone());
+
             // Provide access to twitch manager and event sender
             app.manage(event_tx.clone());
             app.manage(twitch_manager.clone());
@@ -102,6 +150,14 @@ pub fn run() {
             _ = tauri::async_runtime::spawn(attempt_twitch_auth_existing_token(
                 app_data.clone(),
                 twitch_manager.clone(),
+                StoredTwitchAccount::Broadcaster,
+            ));
+
+            // Attempt to authenticate the bot account using the saved token
+            _ = tauri::async_runtime::spawn(attempt_twitch_auth_existing_token(
+                app_data.clone(),
+                twitch_manager.clone(),
+                StoredTwitchAccount::Bot,
             ));
 
             // Initialize script actor
@@ -110,18 +166,14 @@ pub fn run() {
                 event_tx.clone(),
                 db.clone(),
                 twitch_manager.clone(),
+                event_ctx.cooldowns.clone(),
+                event_ctx.groups.clone(),
             );
 
             tauri::async_runtime::block_on(script::events::init_global_script_event_actor(actor));
 
             // Handle events triggered by twitch
-            _ = tauri::async_runtime::spawn(process_twitch_events(
-                db.clone(),
-                twitch_manager.clone(),
-                script_handle,
-                event_tx.clone(),
-                twitch_event_rx,
-            ));
+            _ = tauri::async_runtime::spawn(process_twitch_events(event_ctx, twitch_event_rx));
 
             // Run HTTP server
             _ = tauri::async_runtime::spawn(http::server::start(
@@ -151,6 +203,7 @@ pub fn run() {
             commands::data::get_app_data,
             commands::data::get_runtime_app_data,
             commands::data::set_app_data,
+            commands::data::set_paused,
             commands::data::upload_file,
             commands::data::update_hotkeys,
             commands::data::get_overlay_url,
@@ -158,8 +211,17 @@ pub fn run() {
             commands::twitch::get_twitch_oauth_uri,
             commands::twitch::is_authenticated,
             commands::twitch::logout,
+            commands::twitch::get_twitch_bot_oauth_uri,
+            commands::twitch::get_twitch_bot_user,
+            commands::twitch::logout_bot,
             commands::twitch::get_redeems_list,
             commands::twitch::refresh_redeems_list,
+            commands::twitch::create_reward,
+            commands::twitch::update_reward,
+            commands::twitch::set_reward_paused,
+            commands::twitch::set_reward_enabled,
+            commands::twitch::delete_reward,
+            commands::twitch::get_twitch_user_by_username,
             // Item manipulation commands
             commands::items::get_item_by_id,
             commands::items::get_items,
@@ -191,14 +253,24 @@ pub fn run() {
             commands::events::get_events,
             commands::events::get_event_by_id,
             commands::events::create_event,
+            commands::events::create_event_with_reward,
             commands::events::update_event,
             commands::events::delete_event,
             commands::events::test_event_by_id,
+            commands::events::get_event_next_run,
             commands::events::update_event_orderings,
             commands::events::get_event_executions,
             commands::events::delete_event_executions,
             commands::events::get_event_logs,
             commands::events::delete_event_logs,
+            // Group commands
+            commands::groups::get_groups,
+            commands::groups::get_group_by_id,
+            commands::groups::create_group,
+            commands::groups::update_group,
+            commands::groups::set_group_enabled,
+            commands::groups::delete_group,
+            commands::groups::update_group_orderings,
         ])
         .build(tauri::generate_context!())
         .expect("error while building tauri application")
@@ -215,49 +287,102 @@ pub fn run() {
         });
 }
 
-/// Attempts to authenticate with twitch using an existing access token
+/// Twitch account that can have a stored access token
+#[derive(Debug, Clone, Copy)]
+enum StoredTwitchAccount {
+    /// Broadcaster account
+    Broadcaster,
+    /// Optional bot account used to send chat messages
+    Bot,
+}
+
+impl StoredTwitchAccount {
+    fn name(self) -> &'static str {
+        match self {
+            StoredTwitchAccount::Broadcaster => "access token",
+            StoredTwitchAccount::Bot => "bot access token",
+        }
+    }
+
+    fn required_scopes(self) -> &'static [Scope] {
+        match self {
+            StoredTwitchAccount::Broadcaster => TWITCH_REQUIRED_SCOPES,
+            StoredTwitchAccount::Bot => TWITCH_BOT_REQUIRED_SCOPES,
+        }
+    }
+
+    /// Reads the stored access token and scopes for the account
+    fn credentials(self, config: &TwitchConfig) -> Option<(AccessToken, Vec<Scope>)> {
+        let (access_token, scopes) = match self {
+            StoredTwitchAccount::Broadcaster => (&config.access_token, &config.scopes),
+            StoredTwitchAccount::Bot => (&config.bot_access_token, &config.bot_scopes),
+        };
+
+        Some((access_token.clone()?, scopes.clone()?))
+    }
+
+    /// Clears the stored access token and scopes for the account
+    fn clear(self, config: &mut TwitchConfig) {
+        match self {
+            StoredTwitchAccount::Broadcaster => {
+                config.access_token = None;
+                config.scopes = None;
+            }
+            StoredTwitchAccount::Bot => {
+                config.bot_access_token = None;
+                config.bot_scopes = None;
+            }
+        }
+    }
+}
+
+/// Attempts to authenticate an account with twitch using its existing
+/// access token, outdated or invalid tokens are cleared
 async fn attempt_twitch_auth_existing_token(
     app_data_store: AppDataStore,
     twitch_manager: Arc<TwitchManager>,
+    account: StoredTwitchAccount,
 ) {
+    let name = account.name();
+
     // Read existing access token
     let (access_token, scopes) = {
         let app_data = app_data_store.read().await;
-        match (
-            &app_data.twitch_config.access_token,
-            &app_data.twitch_config.scopes,
-        ) {
-            (Some(access_token), Some(scopes)) => (access_token.clone(), scopes.clone()),
-            _ => return,
+        match account.credentials(&app_data.twitch_config) {
+            Some(value) => value,
+            None => return,
         }
     };
 
-    for required_scope in TWITCH_REQUIRED_SCOPES {
-        if !scopes.contains(required_scope) {
-            info!("logging out current access token, missing required scope");
-
-            // Clear outdated / invalid access token
-            _ = app_data_store
-                .write(|app_data| {
-                    app_data.twitch_config.access_token = None;
-                })
-                .await;
+    let has_required_scopes = account
+        .required_scopes()
+        .iter()
+        .all(|required_scope| scopes.contains(required_scope));
+
+    if !has_required_scopes {
+        info!("logging out current {name}, missing required scope");
+    } else {
+        let result = match account {
+            StoredTwitchAccount::Broadcaster => {
+                twitch_manager
+                    .attempt_auth_existing_token(access_token)
+                    .await
+            }
+            StoredTwitchAccount::Bot => {
+                twitch_manager
+                    .attempt_bot_auth_existing_token(access_token)
+                    .await
+            }
+        };
 
-            return;
+        match result {
+            Ok(_) => return,
+            Err(err) => error!("stored {name} is invalid: {}", err),
         }
     }
 
-    if let Err(err) = twitch_manager
-        .attempt_auth_existing_token(access_token)
-        .await
-    {
-        error!("stored access token is invalid: {}", err);
-
-        // Clear outdated / invalid access token
-        _ = app_data_store
-            .write(|app_data| {
-                app_data.twitch_config.access_token = None;
-            })
-            .await;
-    }
+    // Clear outdated / invalid access token
+    _ = app_data_store
+        .write(|app_data| account.clear(&mut app_data.twitch_config))
+        .await;
 }
@@ -1,4 +1,5 @@
 use anyhow::Context;
+use chrono::Utc;
 use interlink::prelude::*;
 use log::error;
 use sea_orm::{prelude::DateTimeUtc, DatabaseConnection, ModelTrait};
@@ -14,11 +15,17 @@ use crate::{
     database::entity::{
         command_logs::{CommandLogsModel, CreateCommandLog},
         event_logs::{CreateEventLog, EventLogsModel},
+        groups::UpdateGroup,
         key_value::{CreateKeyValue, KeyValueModel, KeyValueType},
         shared::LoggingLevelDb,
-        ItemModel, SoundModel,
+        CommandModel, EventModel, GroupModel, ItemModel, SoundModel,
+    },
+    events::{
+        cooldowns::{CooldownTarget, CooldownTracker},
+        group_cache::GroupCache,
+        processing::{apply_command_group, apply_event_group},
+        EventMessage, ThrowItemConfig, ThrowItemMessage,
     },
-    events::{EventMessage, ThrowItemConfig, ThrowItemMessage},
     integrations::tts_monster::{TTSMonsterService, TTSMonsterVoice},
     state::app_data::{AppDataStore, ItemWithImpactSoundIds, ItemsWithSounds},
     twitch::manager::{TwitchManager, TwitchUser},
@@ -70,6 +77,12 @@ pub struct ScriptEventActor {
 
     /// Access to the twitch manager
     twitch_manager: Arc<TwitchManager>,
+
+    /// Access to event and command cooldowns
+    cooldowns: CooldownTracker,
+
+    /// Access to the cached groups
+    groups: GroupCache,
 }
 
 impl ScriptEventActor {
@@ -78,12 +91,16 @@ impl ScriptEventActor {
         event_sender: broadcast::Sender<EventMessage>,
         db: DatabaseConnection,
         twitch_manager: Arc<TwitchManager>,
+        cooldowns: CooldownTracker,
+        groups: GroupCache,
     ) -> Self {
         Self {
             app_data,
             event_sender,
             db,
             twitch_manager,
+            cooldowns,
+            groups,
         }
     }
 }
@@ -101,7 +118,7 @@ impl Handler<TwitchSendChat> for ScriptEventActor {
     fn handle(&mut self, msg: TwitchSendChat, _ctx: &mut ServiceContext<Self>) -> Self::Response {
         let twitch_manager = self.twitch_manager.clone();
         Fr::new_box(async move {
-            _ = twitch_manager.send_chat_message(&msg.message).await?;
+            twitch_manager.send_chat_message(&msg.message).await?;
             Ok(())
         })
     }
@@ -212,6 +229,32 @@ impl Handler<TwitchIsVip> for ScriptEventActor {
     }
 }
 
+/// Message to trigger sending a reply to a message in Twitch chat
+#[derive(Message)]
+#[msg(rtype = "anyhow::Result<()>")]
+pub struct TwitchSendChatReply {
+    pub message: String,
+    pub reply_to: MsgId,
+}
+
+impl Handler<TwitchSendChatReply> for ScriptEventActor {
+    type Response = Fr<TwitchSendChatReply>;
+
+    fn handle(
+        &mut self,
+        msg: TwitchSendChatReply,
+        _ctx: &mut ServiceContext<Self>,
+    ) -> Self::Response {
+        let twitch_manager = self.twitch_manager.clone();
+        Fr::new_box(async move {
+            twitch_manager
+                .send_chat_reply(&msg.message, msg.reply_to)
+                .await?;
+            Ok(())
+        })
+    }
+}
+
 /// Message to trigger sending an announcement message to Twitch chat
 #[derive(Message)]
 #[msg(rtype = "anyhow::Result<()>")]
@@ -230,7 +273,7 @@ impl Handler<TwitchSendChatAnnouncement> for ScriptEventActor {
     ) -> Self::Response {
         let twitch_manager = self.twitch_manager.clone();
         Fr::new_box(async move {
-            _ = twitch_manager
+            twitch_manager
                 .send_chat_announcement_message(msg.message, msg.color)
                 .await?;
             Ok(())
@@ -306,6 +349,56 @@ impl Handler<TwitchSendShoutout> for ScriptEventActor {
     }
 }
 
+/// Message to create a poll
+#[derive(Message)]
+#[msg(rtype = "anyhow::Result<String>")]
+pub struct TwitchCreatePoll {
+    pub title: String,
+    pub choices: Vec<String>,
+    pub duration: i64,
+}
+
+impl Handler<TwitchCreatePoll> for ScriptEventActor {
+    type Response = Fr<TwitchCreatePoll>;
+
+    fn handle(&mut self, msg: TwitchCreatePoll, _ctx: &mut ServiceContext<Self>) -> Self::Response {
+        let twitch_manager = self.twitch_manager.clone();
+        Fr::new_box(async move {
+            let poll = twitch_manager
+                .create_poll(msg.title, msg.choices, msg.duration)
+                .await?;
+            Ok(poll.id.to_string())
+        })
+    }
+}
+
+/// Message to create a prediction
+#[derive(Message)]
+#[msg(rtype = "anyhow::Result<String>")]
+pub struct TwitchCreatePrediction {
+    pub title: String,
+    pub outcomes: Vec<String>,
+    pub prediction_window: i64,
+}
+
+impl Handler<TwitchCreatePrediction> for ScriptEventActor {
+    type Response = Fr<TwitchCreatePrediction>;
+
+    fn handle(
+        &mut self,
+        msg: TwitchCreatePrediction,
+        _ctx: &mut ServiceContext<Self>,
+    ) -> Self::Response {
+        let twitch_manager = self.twitch_manager.clone();
+        Fr::new_box(async move {
+            let prediction = twitch_manager
+                .create_prediction(msg.title, msg.outcomes, msg.prediction_window)
+                .await?;
+            Ok(prediction.id.to_string())
+        })
+    }
+}
+
 /// Message to set a key value on the key value store
 #[derive(Message)]
 #[msg(rtype = "anyhow::Result<()>")]
@@ -378,6 +471,61 @@ impl Handler<KvGet> for ScriptEventActor {
     }
 }
 
+/// Message to get a group by name
+#[derive(Message)]
+#[msg(rtype = "anyhow::Result<Option<GroupModel>>")]
+pub struct GetGroupByName {
+    pub name: String,
+}
+
+impl Handler<GetGroupByName> for ScriptEventActor {
+    type Response = Fr<GetGroupByName>;
+
+    fn handle(&mut self, msg: GetGroupByName, _ctx: &mut ServiceContext<Self>) -> Self::Response {
+        let db = self.db.clone();
+        Fr::new_box(async move {
+            let group = GroupModel::get_by_name(&db, &msg.name).await?;
+            Ok(group)
+        })
+    }
+}
+
+/// Message to enable or disable a group
+#[derive(Message)]
+#[msg(rtype = "anyhow::Result<()>")]
+pub struct SetGroupEnabled {
+    pub group_id: Uuid,
+    pub enabled: bool,
+}
+
+impl Handler<SetGroupEnabled> for ScriptEventActor {
+    type Response = Fr<SetGroupEnabled>;
+
+    fn handle(&mut self, msg: SetGroupEnabled, _ctx: &mut ServiceContext<Self>) -> Self::Response {
+        let db = self.db.clone();
+        let groups = self.groups.clone();
+        Fr::new_box(async move {
+            let group = GroupModel::get_by_id(&db, msg.group_id)
+                .await?
+                .context("unknown group")?;
+
+            group
+                .update(
+                    &db,
+                    UpdateGroup {
+                        enabled: Some(msg.enabled),
+                        ..Default::default()
+                    },
+                )
+                .await?;
+
+            groups.invalidate().await;
+
+            Ok(())
+        })
+    }
+}
+
 /// Message to get sounds with a matching name
 #[derive(Message)]
 #[msg(rtype = "anyhow::Result<Vec<SoundModel>>")]
@@ -417,6 +565,92 @@ impl Handler<GetSoundsByIDs> for ScriptEventActor {
     }
 }
 
+/// Message to get the remaining cooldown in milliseconds for an event
+#[derive(Message)]
+#[msg(rtype = "anyhow::Result<Option<u64>>")]
+pub struct GetEventCooldownRemaining {
+    pub event_id: Uuid,
+    pub user_id: Option<UserId>,
+}
+
+impl Handler<GetEventCooldownRemaining> for ScriptEventActor {
+    type Response = Fr<GetEventCooldownRemaining>;
+
+    fn handle(
+        &mut self,
+        msg: GetEventCooldownRemaining,
+        _ctx: &mut ServiceContext<Self>,
+    ) -> Self::Response {
+        let db = self.db.clone();
+        let cooldowns = self.cooldowns.clone();
+        let groups = self.groups.clone();
+        Fr::new_box(async move {
+            let mut event = EventModel::get_by_id(&db, msg.event_id)
+                .await?
+                .context("unknown event")?;
+
+            apply_event_group(&db, &groups, &mut event).await?;
+
+            if !event.cooldown.enabled {
+                return Ok(None);
+            }
+
+            let remaining = cooldowns.get_remaining(
+                CooldownTarget::Event(event.id),
+                msg.user_id.as_ref(),
+                event.cooldown.duration,
+                event.cooldown.per_user,
+                Utc::now(),
+            );
+
+            Ok(remaining.map(|remaining| remaining.as_millis() as u64))
+        })
+    }
+}
+
+/// Message to get the remaining cooldown in milliseconds for a command
+#[derive(Message)]
+#[msg(rtype = "anyhow::Result<Option<u64>>")]
+pub struct GetCommandCooldownRemaining {
+    pub command_id: Uuid,
+    pub user_id: Option<UserId>,
+}
+
+impl Handler<GetCommandCooldownRemaining> for ScriptEventActor {
+    type Response = Fr<GetCommandCooldownRemaining>;
+
+    fn handle(
+        &mut self,
+        msg: GetCommandCooldownRemaining,
+        _ctx: &mut ServiceContext<Self>,
+    ) -> Self::Response {
+        let db = self.db.clone();
+        let cooldowns = self.cooldowns.clone();
+        let groups = self.groups.clone();
+        Fr::new_box(async move {
+            let mut command = CommandModel::get_by_id(&db, msg.command_id)
+                .await?
+                .context("unknown command")?;
+
+            apply_command_group(&db, &groups, &mut command).await?;
+
+            if !command.cooldown.enabled {
+                return Ok(None);
+            }
+
+            let remaining = cooldowns.get_remaining(
+                CooldownTarget::Command(command.id),
+                msg.user_id.as_ref(),
+                command.cooldown.duration,
+                command.cooldown.per_user,
+                Utc::now(),
+            );
+
+            Ok(remaining.map(|remaining| remaining.as_millis() as u64))
+        })
+    }
+}
+
 /// Message to get sounds with a matching name
 #[derive(Message)]
 #[msg(rtype = "anyhow::Result<Vec<ItemWithImpactSoundIds>>")]
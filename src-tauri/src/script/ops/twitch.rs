@@ -1,8 +1,9 @@
 use crate::{
     script::events::{
-        global_script_event, TwitchCreateStreamMarker, TwitchDeleteAllChatMessages,
-        TwitchDeleteChatMessage, TwitchGetFollower, TwitchGetUserByUsername, TwitchIsMod,
-        TwitchIsVip, TwitchSendChat, TwitchSendChatAnnouncement, TwitchSendShoutout,
+        global_script_event, TwitchCreatePoll, TwitchCreatePrediction, TwitchCreateStreamMarker,
+        TwitchDeleteAllChatMessages, TwitchDeleteChatMessage, TwitchGetFollower,
+        TwitchGetUserByUsername, TwitchIsMod, TwitchIsVip, TwitchSendChat,
+        TwitchSendChatAnnouncement, TwitchSendChatReply, TwitchSendShoutout,
     },
     twitch::manager::TwitchUser,
 };
@@ -24,6 +25,22 @@ pub async fn op_twitch_send_chat(#[string] message: String) -> anyhow::Result<()
         .context("failed to send event")?
 }
 
+/// Operation for sending a chat message reply from JS
+#[op2(async)]
+pub async fn op_twitch_send_chat_reply(
+    #[string] message: String,
+    #[string] reply_to: String,
+) -> anyhow::Result<()> {
+    debug!("requested sending twitch chat reply to {reply_to}: {message}");
+
+    global_script_event(TwitchSendChatReply {
+        message,
+        reply_to: MsgId::new(reply_to),
+    })
+    .await
+    .context("failed to send event")?
+}
+
 /// Operation for sending a chat message from JS
 #[op2(async)]
 pub async fn op_twitch_send_chat_announcement(
@@ -97,6 +114,38 @@ pub async fn op_twitch_send_shoutout(#[string] user_id: String) -> anyhow::Resul
     .context("failed to send event")?
 }
 
+#[op2(async)]
+#[string]
+pub async fn op_twitch_create_poll(
+    #[string] title: String,
+    #[serde] choices: Vec<String>,
+    duration: u32,
+) -> anyhow::Result<String> {
+    global_script_event(TwitchCreatePoll {
+        title,
+        choices,
+        duration: duration as i64,
+    })
+    .await
+    .context("failed to send event")?
+}
+
+#[op2(async)]
+#[string]
+pub async fn op_twitch_create_prediction(
+    #[string] title: String,
+    #[serde] outcomes: Vec<String>,
+    prediction_window: u32,
+) -> anyhow::Result<String> {
+    global_script_event(TwitchCreatePrediction {
+        title,
+        outcomes,
+        prediction_window: prediction_window as i64,
+    })
+    .await
+    .context("failed to send event")?
+}
+
 #[op2(async)]
 pub async fn op_twitch_is_mod(#[string] user_id: String) -> anyhow::Result<bool> {
     global_script_event(TwitchIsMod {
@@ -1,11 +1,12 @@
 use crate::{
-    database::entity::SoundModel,
+    database::entity::{GroupModel, SoundModel},
     events::ThrowItemConfig,
     integrations::tts_monster::TTSMonsterVoice,
     script::events::{
-        global_script_event, GetItemsByIDs, GetItemsByNames, GetSoundsByIDs, GetSoundsByNames,
-        PlaySound, PlaySoundSeq, TTSGenerate, TTSGenerateParsed, TTSGetVoices, ThrowItems,
-        TriggerHotkey, TriggerHotkeyByName,
+        global_script_event, GetCommandCooldownRemaining, GetEventCooldownRemaining,
+        GetGroupByName, GetItemsByIDs, GetItemsByNames, GetSoundsByIDs, GetSoundsByNames,
+        PlaySound, PlaySoundSeq, SetGroupEnabled, TTSGenerate, TTSGenerateParsed, TTSGetVoices,
+        ThrowItems, TriggerHotkey, TriggerHotkeyByName,
     },
     state::app_data::{ItemWithImpactSoundIds, ItemsWithSounds},
 };
@@ -13,6 +14,7 @@ use anyhow::Context;
 use chrono::Utc;
 use deno_core::op2;
 use serde::Deserialize;
+use twitch_api::types::UserId;
 use uuid::Uuid;
 
 #[op2(async)]
@@ -95,6 +97,59 @@ pub async fn op_vtftk_get_sounds_by_ids(
         .context("failed to send event")?
 }
 
+/// Get the remaining cooldown for an event in milliseconds
+#[op2(async)]
+#[serde]
+pub async fn op_vtftk_get_event_cooldown_remaining(
+    #[serde] event_id: Uuid,
+    #[string] user_id: Option<String>,
+) -> anyhow::Result<Option<u64>> {
+    global_script_event(GetEventCooldownRemaining {
+        event_id,
+        user_id: user_id.map(UserId::new),
+    })
+    .await
+    .context("failed to send event")?
+}
+
+/// Get the remaining cooldown for a command in milliseconds
+#[op2(async)]
+#[serde]
+pub async fn op_vtftk_get_command_cooldown_remaining(
+    #[serde] command_id: Uuid,
+    #[string] user_id: Option<String>,
+) -> anyhow::Result<Option<u64>> {
+    global_script_event(GetCommandCooldownRemaining {
+        command_id,
+        user_id: user_id.map(UserId::new),
+    })
+    .await
+    .context("failed to send event")?
+}
+
+/// Get an event and command group by name
+#[op2(async)]
+#[serde]
+pub async fn op_vtftk_get_group_by_name(
+    #[string] name: String,
+) -> anyhow::Result<Option<GroupModel>> {
+    global_script_event(GetGroupByName { name })
+        .await
+        .context("failed to send event")?
+}
+
+/// Enable or disable all the events and commands in a group
+#[op2(async)]
+#[serde]
+pub async fn op_vtftk_set_group_enabled(
+    #[serde] group_id: Uuid,
+    enabled: bool,
+) -> anyhow::Result<()> {
+    global_script_event(SetGroupEnabled { group_id, enabled })
+        .await
+        .context("failed to send event")?
+}
+
 #[op2(async)]
 #[string]
 pub async fn op_vtftk_play_sound(#[string] src: String, volume: f32) -> anyhow::Result<()> {
@@ -5,16 +5,19 @@ use crate::{
         kv::{op_kv_get, op_kv_remove, op_kv_set},
         logging::op_log,
         twitch::{
-            op_twitch_create_stream_marker, op_twitch_delete_all_chat_messages,
-            op_twitch_delete_chat_message, op_twitch_get_follower, op_twitch_get_user_by_username,
-            op_twitch_is_mod, op_twitch_is_vip, op_twitch_send_chat,
-            op_twitch_send_chat_announcement, op_twitch_send_shoutout,
+            op_twitch_create_poll, op_twitch_create_prediction, op_twitch_create_stream_marker,
+            op_twitch_delete_all_chat_messages, op_twitch_delete_chat_message,
+            op_twitch_get_follower, op_twitch_get_user_by_username, op_twitch_is_mod,
+            op_twitch_is_vip, op_twitch_send_chat, op_twitch_send_chat_announcement,
+            op_twitch_send_chat_reply, op_twitch_send_shoutout,
         },
         vtftk::{
-            op_vtftk_get_items_by_ids, op_vtftk_get_items_by_names, op_vtftk_get_sounds_by_ids,
-            op_vtftk_get_sounds_by_names, op_vtftk_play_sound, op_vtftk_play_sound_seq,
-            op_vtftk_throw_items, op_vtftk_trigger_vt_hotkey, op_vtftk_trigger_vt_hotkey_by_name,
-            op_vtftk_tts_generate, op_vtftk_tts_generate_parsed, op_vtftk_tts_get_voices,
+            op_vtftk_get_command_cooldown_remaining, op_vtftk_get_event_cooldown_remaining,
+            op_vtftk_get_group_by_name, op_vtftk_get_items_by_ids, op_vtftk_get_items_by_names,
+            op_vtftk_get_sounds_by_ids, op_vtftk_get_sounds_by_names, op_vtftk_play_sound,
+            op_vtftk_play_sound_seq, op_vtftk_set_group_enabled, op_vtftk_throw_items,
+            op_vtftk_trigger_vt_hotkey, op_vtftk_trigger_vt_hotkey_by_name, op_vtftk_tts_generate,
+            op_vtftk_tts_generate_parsed, op_vtftk_tts_get_voices,
         },
     },
 };
@@ -46,6 +49,7 @@ deno_core::extension!(
         op_log,
         // Twitch
         op_twitch_send_chat,
+        op_twitch_send_chat_reply,
         op_twitch_is_mod,
         op_twitch_is_vip,
         op_twitch_get_user_by_username,
@@ -55,6 +59,8 @@ deno_core::extension!(
         op_twitch_delete_chat_message,
         op_twitch_delete_all_chat_messages,
         op_twitch_create_stream_marker,
+        op_twitch_create_poll,
+        op_twitch_create_prediction,
         // KV
         op_kv_get,
         op_kv_set,
@@ -68,6 +74,11 @@ deno_core::extension!(
         op_vtftk_get_items_by_names,
         op_vtftk_get_items_by_ids,
         op_vtftk_throw_items,
+        op_vtftk_get_event_cooldown_remaining,
+        op_vtftk_get_command_cooldown_remaining,
+        // VTFTK Groups
+        op_vtftk_get_group_by_name,
+        op_vtftk_set_group_enabled,
         // VTFTK Hotkeys
         op_vtftk_trigger_vt_hotkey,
         op_vtftk_trigger_vt_hotkey_by_name,
@@ -90,6 +90,10 @@ pub struct AppData {
     pub vtube_studio_config: VTubeStudioConfig,
     pub externals_config: ExternalsConfig,
     pub physics_config: PhysicsConfig,
+    pub outcome_queue_config: OutcomeQueueConfig,
+    pub chat_config: ChatConfig,
+    /// Whether all outcomes are paused
+    pub paused: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -165,6 +169,10 @@ impl Default for VTubeStudioConfig {
 pub struct TwitchConfig {
     pub access_token: Option<AccessToken>,
     pub scopes: Option<Vec<Scope>>,
+    /// Access token for the optional bot account used to send chat messages
+    pub bot_access_token: Option<AccessToken>,
+    /// Scopes authorized for the bot account
+    pub bot_scopes: Option<Vec<Scope>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -306,3 +314,87 @@ impl Default for PhysicsConfig {
         }
     }
 }
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OutcomeQueueConfig {
+    /// Run outcomes one at a time, ignores the max concurrency
+    pub serial: bool,
+    /// Maximum number of outcomes running at once, 0 for no limit
+    pub max_concurrency: usize,
+    /// Queue throws, sounds, chat messages and other outcomes separately
+    /// so each type of outcome has its own limits
+    pub separate_lanes: bool,
+    /// Maximum number of outcomes waiting in the queue, 0 for no limit
+    pub max_length: usize,
+    /// Which outcome to drop when the queue is full
+    pub drop_policy: QueueDropPolicy,
+    /// Time in milliseconds to hold onto a slot after an outcome has been
+    /// produced, gives the overlay time to finish before the next outcome
+    ///
+    /// The overlay does not report when a throw or sound has finished so
+    /// the concurrency limits are time based, with no spacing slots are
+    /// released as soon as the outcome is sent to the overlay
+    pub outcome_spacing: u32,
+}
+
+impl OutcomeQueueConfig {
+    /// Get the maximum number of outcomes that can run at once,
+    /// [None] when there is no limit
+    pub fn concurrency_limit(&self) -> Option<usize> {
+        if self.serial {
+            return Some(1);
+        }
+
+        match self.max_concurrency {
+            0 => None,
+            value => Some(value),
+        }
+    }
+}
+
+/// Default time in milliseconds to hold onto a slot after an outcome,
+/// roughly the time a default throw takes to land
+const DEFAULT_OUTCOME_SPACING: u32 = 1500;
+
+impl Default for OutcomeQueueConfig {
+    fn default() -> Self {
+        Self {
+            serial: false,
+            max_concurrency: 0,
+            separate_lanes: true,
+            max_length: 0,
+            drop_policy: QueueDropPolicy::default(),
+            outcome_spacing: DEFAULT_OUTCOME_SPACING,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChatConfig {
+    /// Prefix added to each part after the first when a long chat
+    /// message is split into multiple parts
+    pub continuation_prefix: String,
+    /// Send chat messages as the bot account when one is connected
+    pub send_as_bot: bool,
+}
+
+impl Default for ChatConfig {
+    fn default() -> Self {
+        Self {
+            continuation_prefix: String::new(),
+            send_as_bot: true,
+        }
+    }
+}
+
+/// Determines which outcome is dropped when the outcome queue is full
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default)]
+pub enum QueueDropPolicy {
+    /// Drop the new outcome
+    #[default]
+    DropNewest,
+    /// Drop the outcome that has been waiting the longest
+    DropOldest,
+}
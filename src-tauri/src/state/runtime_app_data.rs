@@ -1,3 +1,4 @@
+use crate::twitch::chat_queue::ChatQueueStatus;
 use serde::{Deserialize, Deserializer, Serialize};
 use std::{fmt::Debug, sync::Arc};
 use tauri::{AppHandle, Emitter};
@@ -66,6 +67,18 @@ pub struct RuntimeAppData {
 
     /// Current number of active connected overlays
     pub active_overlay_count: u32,
+
+    /// Whether the twitch stream is currently live
+    pub is_live: bool,
+
+    /// Number of outcomes waiting in the outcome queue
+    pub outcome_queue_depth: usize,
+
+    /// Current status of the outgoing chat queue
+    pub chat_queue: ChatQueueStatus,
+
+    /// Whether all outcomes are paused
+    pub paused: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -1,8 +1,16 @@
 use anyhow::Context;
+use log::error;
+use serde::Deserialize;
 use tauri::{
-    menu::{IconMenuItem, Menu, MenuItem},
+    menu::{CheckMenuItem, IconMenuItem, Menu, MenuItem},
     tray::TrayIconBuilder,
-    App, AppHandle, Manager,
+    App, AppHandle, Listener, Manager, Wry,
+};
+use tokio::sync::broadcast;
+
+use crate::{
+    events::{pause::set_outcomes_paused, queue::OutcomeQueue, EventMessage},
+    state::{app_data::AppDataStore, runtime_app_data::RuntimeAppDataStore},
 };
 
 #[cfg(not(debug_assertions))]
@@ -19,8 +27,25 @@ pub fn create_tray_menu(app: &mut App) -> anyhow::Result<()> {
 
     let title_i = IconMenuItem::new(app, TRAY_NAME, false, Some(icon.clone()), None::<&str>)?;
     let open_i = MenuItem::with_id(app, "open", "Open", true, None::<&str>)?;
+
+    let paused = app.state::<AppDataStore>().blocking_read().paused;
+    let pause_i = CheckMenuItem::with_id(
+        app,
+        "pause",
+        "Pause all outcomes",
+        true,
+        paused,
+        None::<&str>,
+    )?;
+
+    // Keep the check mark in sync when paused from chat or the app
+    app.listen_any("runtime_app_data_changed", {
+        let pause_i = pause_i.clone();
+        move |event| handle_runtime_app_data_changed(event.payload(), &pause_i)
+    });
+
     let quit_i = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
-    let menu = Menu::with_items(app, &[&title_i, &open_i, &quit_i])?;
+    let menu = Menu::with_items(app, &[&title_i, &open_i, &pause_i, &quit_i])?;
 
     TrayIconBuilder::new()
         .icon(icon)
@@ -30,6 +55,8 @@ pub fn create_tray_menu(app: &mut App) -> anyhow::Result<()> {
         .on_menu_event(move |app, event| {
             if event.id() == open_i.id() {
                 handle_open_clicked(app).expect("failed to open")
+            } else if event.id == pause_i.id() {
+                handle_pause_clicked(app, pause_i.clone())
             } else if event.id == quit_i.id() {
                 handle_quit_clicked(app).expect("failed to quit")
             }
@@ -57,6 +84,52 @@ fn handle_open_clicked(app: &AppHandle) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Handles "Pause all outcomes" being clicked in the tray menu, toggles
+/// the paused state, flushing any queued outcomes when pausing
+fn handle_pause_clicked(app: &AppHandle, pause_i: CheckMenuItem<Wry>) {
+    let app_data = app.state::<AppDataStore>().inner().clone();
+    let runtime_app_data = app.state::<RuntimeAppDataStore>().inner().clone();
+    let event_sender = app
+        .state::<broadcast::Sender<EventMessage>>()
+        .inner()
+        .clone();
+    let outcome_queue = app.state::<OutcomeQueue>().inner().clone();
+
+    tauri::async_runtime::spawn(async move {
+        let paused = !app_data.read().await.paused;
+
+        if let Err(err) = set_outcomes_paused(
+            &app_data,
+            &runtime_app_data,
+            &event_sender,
+            &outcome_queue,
+            paused,
+            paused,
+        )
+        .await
+        {
+            error!("failed to toggle paused state: {err:?}");
+        }
+
+        _ = pause_i.set_checked(paused);
+    });
+}
+
+/// Paused state from the runtime app data
+#[derive(Deserialize)]
+struct RuntimePausedState {
+    paused: bool,
+}
+
+/// Handles the runtime app data changing, updates the pause check mark
+/// to match the current paused state
+fn handle_runtime_app_data_changed(payload: &str, pause_i: &CheckMenuItem<Wry>) {
+    match serde_json::from_str::<RuntimePausedState>(payload) {
+        Ok(state) => _ = pause_i.set_checked(state.paused),
+        Err(err) => error!("failed to parse runtime app data: {err:?}"),
+    }
+}
+
 /// Handles "Quit" being clicked in the tray menu, closes the
 /// application
 fn handle_quit_clicked(app: &AppHandle) -> anyhow::Result<()> {
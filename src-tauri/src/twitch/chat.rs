@@ -0,0 +1,215 @@
+//! # Chat
+//!
+//! Helpers for sending chat messages within the Twitch limits
+
+use std::collections::VecDeque;
+
+use twitch_api::types::{MsgId, UserId};
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Maximum length in bytes of a single chat message
+pub const MAX_CHAT_MESSAGE_LENGTH: usize = 500;
+
+/// Number of recently sent chat messages to remember
+const SENT_MESSAGE_HISTORY: usize = 100;
+
+/// IDs of the chat messages most recently sent by the app
+#[derive(Default)]
+pub struct SentChatMessages {
+    /// Message IDs, oldest first
+    ids: VecDeque<MsgId>,
+}
+
+impl SentChatMessages {
+    /// Records a message sent by the app, the oldest message
+    /// is forgotten once the history is full
+    pub fn record(&mut self, message_id: MsgId) {
+        if self.ids.len() >= SENT_MESSAGE_HISTORY {
+            self.ids.pop_front();
+        }
+
+        self.ids.push_back(message_id);
+    }
+
+    pub fn contains(&self, message_id: &MsgId) -> bool {
+        self.ids.contains(message_id)
+    }
+}
+
+/// Checks if a chat message was sent by the app. Every message from the
+/// bot account is sent by the app, messages sent as the broadcaster are
+/// only known by the IDs of the messages the app sent
+pub fn is_own_chat_message(
+    user_id: &UserId,
+    message_id: &MsgId,
+    bot_user_id: Option<&UserId>,
+    sent_messages: &SentChatMessages,
+) -> bool {
+    bot_user_id.is_some_and(|bot_user_id| bot_user_id == user_id)
+        || sent_messages.contains(message_id)
+}
+
+/// Splits a chat message into parts no longer than `max_length` bytes
+///
+/// Messages are split on whitespace where possible, words longer than a
+/// whole part are split between graphemes so emotes and emoji are never
+/// cut in half. Each part after the first starts with the `continuation_prefix`
+pub fn split_chat_message(
+    message: &str,
+    max_length: usize,
+    continuation_prefix: &str,
+) -> Vec<String> {
+    let message = message.trim();
+    if message.is_empty() {
+        return Vec::new();
+    }
+
+    if message.len() <= max_length {
+        return vec![message.to_string()];
+    }
+
+    // Prefixes that leave little room for the message itself are ignored
+    let continuation_prefix = if continuation_prefix.len() * 2 > max_length {
+        ""
+    } else {
+        continuation_prefix
+    };
+
+    let part_limit = |parts: &Vec<String>| {
+        if parts.is_empty() {
+            max_length
+        } else {
+            max_length - continuation_prefix.len()
+        }
+    };
+
+    let mut parts = Vec::new();
+    let mut current = String::new();
+
+    for word in message.split_inclusive(char::is_whitespace) {
+        if current.len() + word.trim_end().len() <= part_limit(&parts) {
+            current.push_str(word);
+            continue;
+        }
+
+        push_part(&mut parts, &mut current, continuation_prefix);
+
+        if word.trim_end().len() <= part_limit(&parts) {
+            current.push_str(word);
+            continue;
+        }
+
+        // Word is longer than a whole part, split it between graphemes
+        for grapheme in word.graphemes(true) {
+            if current.len() + grapheme.len() > part_limit(&parts) {
+                push_part(&mut parts, &mut current, continuation_prefix);
+            }
+
+            current.push_str(grapheme);
+        }
+    }
+
+    push_part(&mut parts, &mut current, continuation_prefix);
+
+    parts
+}
+
+/// Moves the current part into the list of parts, adding the continuation
+/// prefix to any part after the first
+fn push_part(parts: &mut Vec<String>, current: &mut String, continuation_prefix: &str) {
+    let part = current.trim();
+
+    if !part.is_empty() {
+        let part = if parts.is_empty() {
+            part.to_string()
+        } else {
+            format!("{continuation_prefix}{part}")
+        };
+
+        parts.push(part);
+    }
+
+    current.clear();
+}
+
+#[cfg(test)]
+mod test {
+    use twitch_api::types::{MsgId, UserId};
+
+    use super::{is_own_chat_message, split_chat_message, SentChatMessages, SENT_MESSAGE_HISTORY};
+
+    #[test]
+    fn test_split_short_message() {
+        assert_eq!(split_chat_message(" hello ", 10, ""), vec!["hello"]);
+        assert!(split_chat_message("   ", 10, "").is_empty());
+    }
+
+    #[test]
+    fn test_split_on_whitespace() {
+        let parts = split_chat_message("one two three four", 9, "");
+        assert_eq!(parts, vec!["one two", "three", "four"]);
+    }
+
+    #[test]
+    fn test_split_continuation_prefix() {
+        let parts = split_chat_message("one two three four", 10, "..");
+        assert_eq!(parts, vec!["one two", "..three", "..four"]);
+    }
+
+    #[test]
+    fn test_split_long_word_on_graphemes() {
+        // Family emoji is a single grapheme made of multiple characters
+        let family = "👨‍👩‍👧";
+        let message = family.repeat(4);
+        let parts = split_chat_message(&message, family.len() * 3, "");
+
+        assert_eq!(parts, vec![family.repeat(3), family.to_string()]);
+        assert!(parts.iter().all(|part| part.len() <= family.len() * 3));
+    }
+
+    #[test]
+    fn test_own_chat_message() {
+        let broadcaster = UserId::from_static("1");
+        let bot = UserId::from_static("2");
+        let viewer = UserId::from_static("3");
+
+        let mut sent = SentChatMessages::default();
+        sent.record(MsgId::from_static("sent"));
+
+        let other = MsgId::from_static("other");
+        let sent_id = MsgId::from_static("sent");
+
+        // Messages from the bot account are always from the app
+        assert!(is_own_chat_message(&bot, &other, Some(&bot), &sent));
+
+        // Broadcaster messages are only from the app when the app sent them
+        assert!(is_own_chat_message(
+            &broadcaster,
+            &sent_id,
+            Some(&bot),
+            &sent
+        ));
+        assert!(!is_own_chat_message(
+            &broadcaster,
+            &other,
+            Some(&bot),
+            &sent
+        ));
+        assert!(!is_own_chat_message(&broadcaster, &other, None, &sent));
+
+        assert!(!is_own_chat_message(&viewer, &other, Some(&bot), &sent));
+    }
+
+    #[test]
+    fn test_sent_message_history() {
+        let mut sent = SentChatMessages::default();
+
+        for index in 0..=SENT_MESSAGE_HISTORY {
+            sent.record(MsgId::new(index.to_string()));
+        }
+
+        // Oldest message is forgotten once the history is full
+        assert!(!sent.contains(&MsgId::new("0".to_string())));
+        assert!(sent.contains(&MsgId::new(SENT_MESSAGE_HISTORY.to_string())));
+    }
+}
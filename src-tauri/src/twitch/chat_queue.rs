@@ -0,0 +1,613 @@
+//! # Chat Queue
+//!
+//! Queue for outgoing chat messages, messages are sent one at a time
+//! within the Twitch chat rate limits with higher priority messages
+//! sent first. Low priority messages that have waited too long are
+//! sent ahead of higher priority messages so they are not held back
+//! forever
+
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex, Weak},
+    time::{Duration, Instant},
+};
+
+use anyhow::anyhow;
+use log::debug;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{oneshot, Notify};
+use twitch_api::types::MsgId;
+
+use crate::state::runtime_app_data::RuntimeAppDataStore;
+
+use super::manager::TwitchManager;
+
+/// Window that rate limits are counted over
+const RATE_LIMIT_WINDOW: Duration = Duration::from_secs(30);
+
+/// Messages allowed within the window for regular users
+const RATE_LIMIT_REGULAR: u32 = 20;
+
+/// Messages allowed within the window for the broadcaster, moderators and VIPs
+const RATE_LIMIT_ELEVATED: u32 = 100;
+
+/// Identical messages sent within this window are suppressed, Twitch
+/// drops duplicate messages sent within 30 seconds
+const DUPLICATE_MESSAGE_WINDOW: Duration = Duration::from_secs(30);
+
+/// Maximum number of messages waiting in the queue, once full the oldest
+/// lowest priority message is dropped to make room
+const MAX_QUEUE_LENGTH: usize = 100;
+
+/// Low priority messages waiting longer than this are sent before
+/// higher priority messages
+const MAX_LOW_PRIORITY_WAIT: Duration = Duration::from_secs(60);
+
+/// Priority of an outgoing chat message, higher priority messages
+/// are sent before lower priority messages
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum ChatPriority {
+    /// Messages from timer events
+    Low,
+    /// Messages from events, commands and scripts
+    #[default]
+    Normal,
+    /// Announcements
+    High,
+}
+
+impl ChatPriority {
+    /// All priorities from highest to lowest
+    const ALL: [ChatPriority; 3] = [ChatPriority::High, ChatPriority::Normal, ChatPriority::Low];
+}
+
+/// Outgoing chat message waiting in the queue
+pub enum OutgoingChat {
+    /// Chat message split into parts that are sent in order, parts
+    /// are sent as replies when `reply_to` is set
+    Message {
+        parts: Vec<String>,
+        reply_to: Option<MsgId>,
+    },
+    /// Chat announcement
+    Announcement { message: String, color: String },
+}
+
+/// Status of the chat queue reported to the frontend
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChatQueueStatus {
+    /// Number of messages waiting to be sent
+    pub length: usize,
+    /// Whether messages are waiting on the rate limit
+    pub rate_limited: bool,
+    /// Number of duplicate messages that have been suppressed
+    pub suppressed: u64,
+}
+
+impl OutgoingChat {
+    /// Key used to detect duplicate messages, announcements are never
+    /// treated as duplicates
+    fn duplicate_key(&self) -> Option<DuplicateKey> {
+        match self {
+            OutgoingChat::Message { parts, reply_to } => Some(DuplicateKey {
+                message: parts.join(" "),
+                reply_to: reply_to.clone(),
+            }),
+            OutgoingChat::Announcement { .. } => None,
+        }
+    }
+}
+
+/// Identifies a sent message for duplicate suppression, replies to
+/// different messages are not duplicates of each other
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct DuplicateKey {
+    message: String,
+    reply_to: Option<MsgId>,
+}
+
+struct QueuedChat {
+    chat: OutgoingChat,
+    tx: oneshot::Sender<anyhow::Result<()>>,
+    /// When the chat was added to the queue
+    queued_at: Instant,
+}
+
+/// Shared queue for outgoing chat messages
+#[derive(Clone)]
+pub struct ChatQueue {
+    inner: Arc<ChatQueueInner>,
+}
+
+struct ChatQueueInner {
+    /// Current queue state
+    state: Mutex<ChatQueueState>,
+    /// Notifier for new messages being queued
+    notify: Notify,
+    /// Runtime app data to report the queue status to
+    runtime_app_data: RuntimeAppDataStore,
+}
+
+#[derive(Default)]
+struct ChatQueueState {
+    /// Messages waiting to be sent for each priority, oldest first
+    waiting: HashMap<ChatPriority, VecDeque<QueuedChat>>,
+    /// Rate limit for sending messages
+    bucket: TokenBucket,
+    /// Messages sent recently and when they were sent
+    recent: HashMap<DuplicateKey, Instant>,
+    /// Whether messages are waiting on the rate limit
+    rate_limited: bool,
+    /// Number of duplicate messages suppressed
+    suppressed: u64,
+}
+
+impl ChatQueue {
+    pub fn new(runtime_app_data: RuntimeAppDataStore) -> Self {
+        Self {
+            inner: Arc::new(ChatQueueInner {
+                state: Default::default(),
+                notify: Notify::new(),
+                runtime_app_data,
+            }),
+        }
+    }
+
+    /// Queues a message to be sent, waits until the message has been sent
+    ///
+    /// Chat messages identical to a recently sent message are suppressed
+    pub async fn send(&self, priority: ChatPriority, chat: OutgoingChat) -> anyhow::Result<()> {
+        let rx = {
+            let state = &mut *self.inner.state.lock().expect("chat queue lock poisoned");
+
+            let now = Instant::now();
+
+            if state.suppress_duplicate(&chat, now) {
+                None
+            } else {
+                let (tx, rx) = oneshot::channel();
+                let queued = QueuedChat {
+                    chat,
+                    tx,
+                    queued_at: now,
+                };
+
+                if let Some(dropped) = state.push(priority, queued) {
+                    debug!("chat queue is full, dropping message");
+                    _ = dropped
+                        .tx
+                        .send(Err(anyhow!("chat message was dropped from the full queue")));
+                }
+
+                Some(rx)
+            }
+        };
+
+        self.inner.report_status();
+
+        let rx = match rx {
+            Some(value) => value,
+            // Duplicate messages are treated as sent
+            None => return Ok(()),
+        };
+
+        self.inner.notify.notify_one();
+
+        rx.await
+            .map_err(|_| anyhow!("chat message was dropped from the queue"))?
+    }
+}
+
+impl ChatQueueState {
+    /// Number of messages waiting in the queue
+    fn len(&self) -> usize {
+        self.waiting.values().map(VecDeque::len).sum()
+    }
+
+    /// Adds a chat to the queue, when the queue is full the oldest message
+    /// with the lowest priority is removed to make room and returned. The
+    /// chat itself is returned when every waiting message has a higher priority
+    fn push(&mut self, priority: ChatPriority, queued: QueuedChat) -> Option<QueuedChat> {
+        let mut dropped = None;
+
+        if self.len() >= MAX_QUEUE_LENGTH {
+            let lowest = ChatPriority::ALL
+                .iter()
+                .rev()
+                .copied()
+                .take_while(|other| *other <= priority)
+                .find(|other| {
+                    self.waiting
+                        .get(other)
+                        .is_some_and(|waiting| !waiting.is_empty())
+                });
+
+            match lowest {
+                Some(lowest) => {
+                    dropped = self.waiting.get_mut(&lowest).and_then(VecDeque::pop_front);
+                }
+                None => return Some(queued),
+            }
+        }
+
+        self.waiting.entry(priority).or_default().push_back(queued);
+        dropped
+    }
+
+    /// Takes the next message to send, the highest priority message is taken
+    /// unless the oldest low priority message has waited too long
+    fn pop(&mut self, now: Instant) -> Option<QueuedChat> {
+        if let Some(waiting) = self.waiting.get_mut(&ChatPriority::Low) {
+            let is_overdue = waiting.front().is_some_and(|queued| {
+                now.saturating_duration_since(queued.queued_at) >= MAX_LOW_PRIORITY_WAIT
+            });
+
+            if is_overdue {
+                return waiting.pop_front();
+            }
+        }
+
+        ChatPriority::ALL.iter().find_map(|priority| {
+            self.waiting
+                .get_mut(priority)
+                .and_then(|waiting| waiting.pop_front())
+        })
+    }
+
+    /// Checks if the chat is identical to a recently sent message,
+    /// duplicates are counted as suppressed
+    fn suppress_duplicate(&mut self, chat: &OutgoingChat, now: Instant) -> bool {
+        let key = match chat.duplicate_key() {
+            Some(value) => value,
+            None => return false,
+        };
+
+        // Remove expired entries
+        self.recent
+            .retain(|_, sent_at| now.duration_since(*sent_at) < DUPLICATE_MESSAGE_WINDOW);
+
+        if !self.recent.contains_key(&key) {
+            return false;
+        }
+
+        debug!("suppressing duplicate chat message: {}", key.message);
+        self.suppressed += 1;
+        true
+    }
+
+    /// Records a successfully sent chat for duplicate suppression
+    fn record_sent(&mut self, chat: &OutgoingChat, now: Instant) {
+        if let Some(key) = chat.duplicate_key() {
+            self.recent.insert(key, now);
+        }
+    }
+}
+
+impl ChatQueueInner {
+    fn status(&self) -> ChatQueueStatus {
+        let state = &*self.state.lock().expect("chat queue lock poisoned");
+        ChatQueueStatus {
+            length: state.len(),
+            rate_limited: state.rate_limited,
+            suppressed: state.suppressed,
+        }
+    }
+
+    /// Reports the current queue status to the runtime app data
+    fn report_status(self: &Arc<Self>) {
+        let inner = self.clone();
+        tokio::spawn(async move {
+            inner
+                .runtime_app_data
+                .write(|runtime_app_data| runtime_app_data.chat_queue = inner.status())
+                .await;
+        });
+    }
+
+    /// Takes the next message to send from the queue
+    fn pop(&self) -> Option<QueuedChat> {
+        let state = &mut *self.state.lock().expect("chat queue lock poisoned");
+        state.pop(Instant::now())
+    }
+
+    /// Checks if a popped chat duplicates a message sent while it was waiting
+    fn suppress_duplicate(&self, chat: &OutgoingChat) -> bool {
+        let state = &mut *self.state.lock().expect("chat queue lock poisoned");
+        state.suppress_duplicate(chat, Instant::now())
+    }
+
+    fn record_sent(&self, chat: &OutgoingChat) {
+        let state = &mut *self.state.lock().expect("chat queue lock poisoned");
+        state.record_sent(chat, Instant::now());
+    }
+
+    fn is_empty(&self) -> bool {
+        let state = &*self.state.lock().expect("chat queue lock poisoned");
+        state.waiting.values().all(|waiting| waiting.is_empty())
+    }
+
+    /// Waits until a message can be sent within the rate limit
+    async fn wait_for_rate_limit(self: &Arc<Self>, elevated: bool) {
+        let limit = if elevated {
+            RATE_LIMIT_ELEVATED
+        } else {
+            RATE_LIMIT_REGULAR
+        };
+
+        loop {
+            let (result, changed) = {
+                let state = &mut *self.state.lock().expect("chat queue lock poisoned");
+                state.bucket.set_limit(limit);
+
+                let result = state.bucket.try_take(Instant::now());
+                let rate_limited = result.is_err();
+                let changed = state.rate_limited != rate_limited;
+                state.rate_limited = rate_limited;
+
+                (result, changed)
+            };
+
+            if changed {
+                self.report_status();
+            }
+
+            match result {
+                Ok(_) => return,
+                Err(wait) => tokio::time::sleep(wait).await,
+            }
+        }
+    }
+}
+
+/// Background task that sends the messages from the chat queue
+pub async fn process_chat_queue(queue: ChatQueue, twitch_manager: Weak<TwitchManager>) {
+    let inner = queue.inner;
+
+    loop {
+        if inner.is_empty() {
+            inner.notify.notified().await;
+            continue;
+        }
+
+        let twitch_manager = match twitch_manager.upgrade() {
+            Some(value) => value,
+            None => return,
+        };
+
+        let elevated = twitch_manager.is_chat_sender_elevated().await;
+
+        // Wait for the rate limit before choosing the message so higher
+        // priority messages queued while waiting are sent first
+        inner.wait_for_rate_limit(elevated).await;
+
+        let queued = match inner.pop() {
+            Some(value) => value,
+            None => continue,
+        };
+
+        // Identical messages may have been sent while this one was waiting
+        if inner.suppress_duplicate(&queued.chat) {
+            inner.report_status();
+            _ = queued.tx.send(Ok(()));
+            continue;
+        }
+
+        inner.report_status();
+
+        let result = match &queued.chat {
+            OutgoingChat::Message { parts, reply_to } => {
+                let mut result = Ok(());
+
+                for (index, part) in parts.iter().enumerate() {
+                    // Each part after the first needs its own slot in the rate limit
+                    if index > 0 {
+                        inner.wait_for_rate_limit(elevated).await;
+                    }
+
+                    result = twitch_manager
+                        .send_chat_message_part(part, reply_to.as_ref())
+                        .await;
+                    if result.is_err() {
+                        break;
+                    }
+                }
+
+                result
+            }
+            OutgoingChat::Announcement { message, color } => {
+                twitch_manager
+                    .send_chat_announcement_request(message.clone(), color.clone())
+                    .await
+            }
+        };
+
+        // Only messages that were actually sent count towards duplicates
+        if result.is_ok() {
+            inner.record_sent(&queued.chat);
+        }
+
+        _ = queued.tx.send(result);
+    }
+}
+
+/// Token bucket rate limit, tokens are refilled continuously over
+/// the rate limit window
+struct TokenBucket {
+    /// Maximum number of tokens
+    limit: u32,
+    /// Currently available tokens
+    tokens: f64,
+    /// Last time tokens were refilled
+    last_refill: Instant,
+}
+
+impl Default for TokenBucket {
+    fn default() -> Self {
+        Self {
+            limit: RATE_LIMIT_REGULAR,
+            tokens: RATE_LIMIT_REGULAR as f64,
+            last_refill: Instant::now(),
+        }
+    }
+}
+
+impl TokenBucket {
+    /// Updates the token limit, available tokens are kept within the new limit
+    fn set_limit(&mut self, limit: u32) {
+        if self.limit == limit {
+            return;
+        }
+
+        self.limit = limit;
+        self.tokens = self.tokens.min(limit as f64);
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.last_refill);
+        let rate = self.limit as f64 / RATE_LIMIT_WINDOW.as_secs_f64();
+
+        self.tokens = (self.tokens + elapsed.as_secs_f64() * rate).min(self.limit as f64);
+        self.last_refill = now;
+    }
+
+    /// Takes a token, when no tokens are available the time until
+    /// the next token is available is returned instead
+    fn try_take(&mut self, now: Instant) -> Result<(), Duration> {
+        self.refill(now);
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            return Ok(());
+        }
+
+        let rate = self.limit as f64 / RATE_LIMIT_WINDOW.as_secs_f64();
+        Err(Duration::from_secs_f64((1.0 - self.tokens) / rate))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, Instant};
+
+    use tokio::sync::oneshot;
+    use twitch_api::types::MsgId;
+
+    use super::{
+        ChatPriority, ChatQueueState, OutgoingChat, QueuedChat, TokenBucket, MAX_LOW_PRIORITY_WAIT,
+        MAX_QUEUE_LENGTH, RATE_LIMIT_REGULAR,
+    };
+
+    fn queued(message: &str, queued_at: Instant) -> QueuedChat {
+        let (tx, _) = oneshot::channel();
+        QueuedChat {
+            chat: OutgoingChat::Message {
+                parts: vec![message.to_string()],
+                reply_to: None,
+            },
+            tx,
+            queued_at,
+        }
+    }
+
+    fn queued_message(queued: Option<QueuedChat>) -> Option<String> {
+        match queued?.chat {
+            OutgoingChat::Message { parts, .. } => Some(parts.join(" ")),
+            OutgoingChat::Announcement { message, .. } => Some(message),
+        }
+    }
+
+    #[test]
+    fn test_token_bucket() {
+        let now = Instant::now();
+        let mut bucket = TokenBucket {
+            limit: RATE_LIMIT_REGULAR,
+            tokens: RATE_LIMIT_REGULAR as f64,
+            last_refill: now,
+        };
+
+        for _ in 0..RATE_LIMIT_REGULAR {
+            assert!(bucket.try_take(now).is_ok());
+        }
+
+        // 20 messages every 30 seconds refills a token every 1.5 seconds
+        let wait = bucket.try_take(now).unwrap_err();
+        assert_eq!(wait.as_millis(), 1500);
+
+        assert!(bucket.try_take(now + Duration::from_secs(2)).is_ok());
+    }
+
+    #[test]
+    fn test_duplicate_suppression() {
+        let now = Instant::now();
+        let mut state = ChatQueueState::default();
+
+        let message = |reply_to: Option<&'static str>| OutgoingChat::Message {
+            parts: vec!["hello".to_string()],
+            reply_to: reply_to.map(MsgId::from_static),
+        };
+
+        // Messages are not duplicates until they have been sent
+        assert!(!state.suppress_duplicate(&message(None), now));
+        assert!(!state.suppress_duplicate(&message(None), now));
+
+        state.record_sent(&message(None), now);
+        assert!(state.suppress_duplicate(&message(None), now));
+
+        // Replies to different messages are not duplicates
+        assert!(!state.suppress_duplicate(&message(Some("a")), now));
+        state.record_sent(&message(Some("a")), now);
+        assert!(state.suppress_duplicate(&message(Some("a")), now));
+        assert!(!state.suppress_duplicate(&message(Some("b")), now));
+
+        // Duplicates expire after the window
+        let later = now + Duration::from_secs(31);
+        assert!(!state.suppress_duplicate(&message(None), later));
+
+        assert_eq!(state.suppressed, 2);
+    }
+
+    #[test]
+    fn test_low_priority_not_starved() {
+        let now = Instant::now();
+        let mut state = ChatQueueState::default();
+
+        state.push(ChatPriority::Low, queued("timer", now));
+        state.push(ChatPriority::Normal, queued("first", now));
+        state.push(ChatPriority::Normal, queued("second", now));
+
+        // Higher priority messages are sent first
+        assert_eq!(queued_message(state.pop(now)).as_deref(), Some("first"));
+
+        // Low priority messages that waited too long are sent next
+        let later = now + MAX_LOW_PRIORITY_WAIT;
+        assert_eq!(queued_message(state.pop(later)).as_deref(), Some("timer"));
+        assert_eq!(queued_message(state.pop(later)).as_deref(), Some("second"));
+        assert!(state.pop(later).is_none());
+    }
+
+    #[test]
+    fn test_queue_length_limit() {
+        let now = Instant::now();
+        let mut state = ChatQueueState::default();
+
+        state.push(ChatPriority::Low, queued("timer", now));
+        for _ in 1..MAX_QUEUE_LENGTH {
+            assert!(state
+                .push(ChatPriority::Normal, queued("normal", now))
+                .is_none());
+        }
+
+        // Oldest low priority message is dropped first
+        let dropped = state.push(ChatPriority::Normal, queued("normal", now));
+        assert_eq!(queued_message(dropped).as_deref(), Some("timer"));
+
+        // Low priority messages are not queued over higher priority messages
+        let dropped = state.push(ChatPriority::Low, queued("timer", now));
+        assert_eq!(queued_message(dropped).as_deref(), Some("timer"));
+
+        let dropped = state.push(ChatPriority::High, queued("announcement", now));
+        assert_eq!(queued_message(dropped).as_deref(), Some("normal"));
+        assert_eq!(state.len(), MAX_QUEUE_LENGTH);
+    }
+}
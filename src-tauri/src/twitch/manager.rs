@@ -1,9 +1,18 @@
-use super::websocket::WebsocketClient;
+use super::{
+    chat::{is_own_chat_message, split_chat_message, SentChatMessages, MAX_CHAT_MESSAGE_LENGTH},
+    chat_queue::{process_chat_queue, ChatPriority, ChatQueue, OutgoingChat},
+    websocket::WebsocketClient,
+};
+use crate::state::{app_data::AppDataStore, runtime_app_data::RuntimeAppDataStore};
 use anyhow::{anyhow, Context};
 use futures::TryStreamExt;
 use log::error;
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::{Duration, Instant},
+};
 use tauri::{AppHandle, Emitter};
 use tokio::{
     join,
@@ -15,6 +24,7 @@ use twitch_api::{
         self,
         channel::{
             channel_points_custom_reward_redemption::Reward, chat::message::Cheer,
+            hypetrain::Contribution, poll::Choice, prediction::PredictionOutcome,
             subscription::message::SubscriptionMessage,
         },
     },
@@ -26,11 +36,22 @@ use twitch_api::{
             SendChatMessageRequest, SendChatMessageResponse,
         },
         moderation::Moderator,
-        points::CustomReward,
+        points::{
+            CreateCustomRewardBody, CreateCustomRewardRequest, CustomReward,
+            CustomRewardRedemptionStatus, DeleteCustomRewardRequest, UpdateCustomRewardBody,
+            UpdateCustomRewardRequest, UpdateRedemptionStatusBody, UpdateRedemptionStatusRequest,
+        },
+        polls::{CreatePollBody, CreatePollRequest, CreatePollResponse, NewPollChoice},
+        predictions::{
+            CreatePredictionBody, CreatePredictionRequest, CreatePredictionResponse,
+            NewPredictionOutcome,
+        },
+        streams::{GetStreamsRequest, Stream},
+        subscriptions::{BroadcasterSubscription, GetBroadcasterSubscriptionsRequest},
         EmptyBody,
     },
     twitch_oauth2::{AccessToken, UserToken},
-    types::{DisplayName, MsgId, RedemptionId, SubscriptionTier, UserId, UserName},
+    types::{DisplayName, MsgId, RedemptionId, StreamId, SubscriptionTier, UserId, UserName},
     HelixClient,
 };
 
@@ -39,6 +60,17 @@ pub struct TwitchManager {
     state: RwLock<TwitchManagerState>,
     tx: broadcast::Sender<TwitchEvent>,
     app_handle: AppHandle,
+    app_data: AppDataStore,
+    /// Queue for outgoing chat messages
+    chat_queue: ChatQueue,
+    /// Token for the optional bot account used to send chat messages
+    bot_token: RwLock<Option<UserToken>>,
+    /// Cached elevated status of the account sending chat messages
+    chat_sender_elevated: RwLock<Option<CachedChatSenderElevated>>,
+    /// Chat messages recently sent by the app
+    sent_messages: RwLock<SentChatMessages>,
+    /// Rewards currently paused by the app while an event is on cooldown
+    cooldown_paused_rewards: RwLock<HashSet<String>>,
 }
 
 pub struct TwitchManagerStateAuthenticated {
@@ -55,8 +87,49 @@ pub struct TwitchManagerStateAuthenticated {
     moderators: Option<Arc<[Moderator]>>,
     /// Current loaded list of vips
     vips: Option<Arc<[Vip]>>,
+
+    /// Current loaded stream details
+    stream: Option<CachedStream>,
+
+    /// Cached subscription details for users
+    subscriptions: HashMap<UserId, CachedSubscription>,
 }
 
+/// Cached details about the current stream
+struct CachedStream {
+    /// ID of the current stream, [None] when not live
+    stream_id: Option<StreamId>,
+    /// When the stream details were loaded
+    loaded_at: Instant,
+}
+
+/// Cached subscription details for a user
+struct CachedSubscription {
+    /// Tier the user is subscribed at, [None] when not subscribed
+    tier: Option<SubscriptionTier>,
+    /// When the subscription details were loaded
+    loaded_at: Instant,
+}
+
+/// Cached elevated status for the account sending chat messages
+struct CachedChatSenderElevated {
+    /// ID of the account sending chat messages
+    user_id: UserId,
+    /// Whether the account is a moderator or VIP
+    elevated: bool,
+    /// When the status was loaded
+    loaded_at: Instant,
+}
+
+/// Duration the current stream details are cached for
+const STREAM_CACHE_DURATION: Duration = Duration::from_secs(60);
+
+/// Duration user subscription details are cached for
+const SUBSCRIPTION_CACHE_DURATION: Duration = Duration::from_secs(60 * 5);
+
+/// Duration the chat sender elevated status is cached for
+const CHAT_SENDER_ELEVATED_CACHE_DURATION: Duration = Duration::from_secs(60 * 5);
+
 #[derive(Default)]
 #[allow(clippy::large_enum_variant)]
 enum TwitchManagerState {
@@ -77,17 +150,34 @@ pub struct TwitchUser {
 }
 
 impl TwitchManager {
-    pub fn new(app_handle: AppHandle) -> (Arc<Self>, broadcast::Receiver<TwitchEvent>) {
+    pub fn new(
+        app_handle: AppHandle,
+        app_data: AppDataStore,
+        runtime_app_data: RuntimeAppDataStore,
+    ) -> (Arc<Self>, broadcast::Receiver<TwitchEvent>) {
         let (tx, rx) = broadcast::channel(10);
-        (
-            Arc::new(Self {
-                helix_client: HelixClient::default(),
-                state: Default::default(),
-                tx,
-                app_handle,
-            }),
-            rx,
-        )
+        let chat_queue = ChatQueue::new(runtime_app_data);
+
+        let twitch_manager = Arc::new(Self {
+            helix_client: HelixClient::default(),
+            state: Default::default(),
+            tx,
+            app_handle,
+            app_data,
+            chat_queue: chat_queue.clone(),
+            bot_token: Default::default(),
+            chat_sender_elevated: Default::default(),
+            sent_messages: Default::default(),
+            cooldown_paused_rewards: Default::default(),
+        });
+
+        // Send queued chat messages in the background
+        tauri::async_runtime::spawn(process_chat_queue(
+            chat_queue,
+            Arc::downgrade(&twitch_manager),
+        ));
+
+        (twitch_manager, rx)
     }
 
     pub async fn attempt_auth_existing_token(
@@ -108,28 +198,177 @@ impl TwitchManager {
         matches!(lock, TwitchManagerState::Authenticated { .. })
     }
 
-    pub async fn send_chat_message(
+    /// Sends a chat message, messages longer than the Twitch limit are
+    /// split into multiple parts that are sent in order
+    pub async fn send_chat_message(&self, message: &str) -> anyhow::Result<()> {
+        self.send_chat_message_with_priority(message, ChatPriority::Normal)
+            .await
+    }
+
+    /// Sends a chat message through the chat queue using the provided priority
+    pub async fn send_chat_message_with_priority(
         &self,
         message: &str,
-    ) -> anyhow::Result<SendChatMessageResponse> {
+        priority: ChatPriority,
+    ) -> anyhow::Result<()> {
+        self.queue_chat_message(message, priority, None).await
+    }
+
+    /// Sends a chat message as a reply to another chat message, the reply
+    /// is threaded under the original message in chat
+    pub async fn send_chat_reply(&self, message: &str, reply_to: MsgId) -> anyhow::Result<()> {
+        self.queue_chat_message(message, ChatPriority::Normal, Some(reply_to))
+            .await
+    }
+
+    /// Splits the chat message into parts and queues them to be sent
+    async fn queue_chat_message(
+        &self,
+        message: &str,
+        priority: ChatPriority,
+        reply_to: Option<MsgId>,
+    ) -> anyhow::Result<()> {
+        let continuation_prefix = self
+            .app_data
+            .read()
+            .await
+            .chat_config
+            .continuation_prefix
+            .clone();
+
+        let parts = split_chat_message(message, MAX_CHAT_MESSAGE_LENGTH, &continuation_prefix);
+        if parts.is_empty() {
+            return Ok(());
+        }
+
+        self.chat_queue
+            .send(priority, OutgoingChat::Message { parts, reply_to })
+            .await
+    }
+
+    /// Whether the account sending chat messages gets the higher chat rate
+    /// limit given to the broadcaster, moderators and VIPs
+    pub(super) async fn is_chat_sender_elevated(&self) -> bool {
+        let (broadcaster_token, sender_token) = match (
+            self.get_user_token().await,
+            self.get_chat_sender_token().await,
+        ) {
+            (Some(broadcaster_token), Some(sender_token)) => (broadcaster_token, sender_token),
+            _ => return false,
+        };
+
+        let sender = sender_token.user_id;
+        if broadcaster_token.user_id == sender {
+            return true;
+        }
+
+        // Use the cached status when it was loaded for the same sender
+        if let Some(cached) = self.chat_sender_elevated.read().await.as_ref() {
+            if cached.user_id == sender
+                && cached.loaded_at.elapsed() < CHAT_SENDER_ELEVATED_CACHE_DURATION
+            {
+                return cached.elevated;
+            }
+        }
+
+        let (vips, mods) = join!(self.get_vip_list(), self.get_moderator_list());
+
+        let elevated = vips.is_ok_and(|vips| vips.iter().any(|vip| vip.user_id == sender))
+            || mods.is_ok_and(|mods| mods.iter().any(|mods| mods.user_id == sender));
+
+        *self.chat_sender_elevated.write().await = Some(CachedChatSenderElevated {
+            user_id: sender,
+            elevated,
+            loaded_at: Instant::now(),
+        });
+
+        elevated
+    }
+
+    /// Get the token for the account that chat messages are sent from, this
+    /// is the bot account when one is connected otherwise the broadcaster
+    async fn get_chat_sender_token(&self) -> Option<UserToken> {
+        let send_as_bot = self.app_data.read().await.chat_config.send_as_bot;
+
+        if send_as_bot {
+            if let Some(token) = self.get_bot_token().await {
+                return Some(token);
+            }
+        }
+
+        self.get_user_token().await
+    }
+
+    /// Checks if the provided `user_id` is the account that chat
+    /// messages are sent from
+    pub async fn is_chat_sender(&self, user_id: &UserId) -> bool {
+        self.get_chat_sender_token()
+            .await
+            .is_some_and(|token| token.user_id.eq(user_id))
+    }
+
+    /// Checks if the chat message from the user with `user_id` was sent by
+    /// the app, either by the bot account or as the broadcaster
+    pub async fn is_own_chat_message(&self, user_id: &UserId, message_id: &MsgId) -> bool {
+        let broadcaster_id = self.get_user_token().await.map(|token| token.user_id);
+
+        // Bot accounts logged in as the broadcaster are also used manually
+        let bot_user_id = self
+            .get_bot_token()
+            .await
+            .map(|token| token.user_id)
+            .filter(|bot_user_id| broadcaster_id.as_ref() != Some(bot_user_id));
+
+        let sent_messages = &*self.sent_messages.read().await;
+        is_own_chat_message(user_id, message_id, bot_user_id.as_ref(), sent_messages)
+    }
+
+    /// Sends a single chat message part, the message must be within
+    /// the Twitch length limit
+    pub(super) async fn send_chat_message_part(
+        &self,
+        message: &str,
+        reply_to: Option<&MsgId>,
+    ) -> anyhow::Result<()> {
         // Obtain twitch access token
         let token = self.get_user_token().await.context("not authenticated")?;
 
-        // Get broadcaster user ID
+        // Obtain the token of the account sending the message
+        let sender_token = self
+            .get_chat_sender_token()
+            .await
+            .context("not authenticated")?;
+
+        // Get broadcaster and sender user IDs
         let user_id = token.user_id.clone();
+        let sender_id = sender_token.user_id.clone();
 
         // Create chat message request
         let request = SendChatMessageRequest::new();
-        let body = SendChatMessageBody::new(user_id.clone(), user_id, message);
+        let mut body = SendChatMessageBody::new(user_id, sender_id, message);
+        body.reply_parent_message_id = reply_to.map(|message_id| message_id.into());
 
-        // Send request and get response
+        // Send request
         let response: SendChatMessageResponse = self
             .helix_client
-            .req_post(request, body, &token)
+            .req_post(request, body, &sender_token)
             .await?
             .data;
 
-        Ok(response)
+        // Twitch can accept the request without sending the message
+        if !response.is_sent {
+            let reason = response
+                .drop_reason
+                .map(|reason| format!("{} ({})", reason.message, reason.code))
+                .unwrap_or_else(|| "unknown reason".to_string());
+
+            error!("chat message was dropped: {reason}");
+            return Err(anyhow!("chat message was dropped: {reason}"));
+        }
+
+        self.sent_messages.write().await.record(response.message_id);
+
+        Ok(())
     }
 
     pub async fn delete_chat_message(&self, message_id: MsgId) -> anyhow::Result<()> {
@@ -174,30 +413,52 @@ impl TwitchManager {
         Ok(())
     }
 
+    /// Sends a chat announcement through the chat queue, announcements
+    /// are sent before other queued messages
     pub async fn send_chat_announcement_message(
         &self,
         message: String,
         color: String,
-    ) -> anyhow::Result<SendChatAnnouncementResponse> {
+    ) -> anyhow::Result<()> {
+        self.chat_queue
+            .send(
+                ChatPriority::High,
+                OutgoingChat::Announcement { message, color },
+            )
+            .await
+    }
+
+    pub(super) async fn send_chat_announcement_request(
+        &self,
+        message: String,
+        color: String,
+    ) -> anyhow::Result<()> {
         // Obtain twitch access token
         let token = self.get_user_token().await.context("not authenticated")?;
 
-        // Get broadcaster user ID
+        // Obtain the token of the account sending the announcement
+        let sender_token = self
+            .get_chat_sender_token()
+            .await
+            .context("not authenticated")?;
+
+        // Get broadcaster and sender user IDs
         let user_id = token.user_id.clone();
+        let sender_id = sender_token.user_id.clone();
 
         // Create chat message request
-        let request = SendChatAnnouncementRequest::new(user_id.clone(), user_id.clone());
+        let request = SendChatAnnouncementRequest::new(user_id, sender_id);
         let body = SendChatAnnouncementBody::new(message, color.as_str())
             .context("failed to create body")?;
 
-        // Send request and get response
-        let response: SendChatAnnouncementResponse = self
+        // Send request
+        let _response: SendChatAnnouncementResponse = self
             .helix_client
-            .req_post(request, body, &token)
+            .req_post(request, body, &sender_token)
             .await?
             .data;
 
-        Ok(response)
+        Ok(())
     }
 
     pub async fn get_user_by_username(&self, username: &str) -> anyhow::Result<Option<TwitchUser>> {
@@ -268,6 +529,296 @@ impl TwitchManager {
         Ok(response)
     }
 
+    pub async fn create_poll(
+        &self,
+        title: String,
+        choices: Vec<String>,
+        duration: i64,
+    ) -> anyhow::Result<CreatePollResponse> {
+        // Obtain twitch access token
+        let token = self.get_user_token().await.context("not authenticated")?;
+
+        // Get broadcaster user ID
+        let user_id = token.user_id.clone();
+
+        let choices: Vec<NewPollChoice> = choices.iter().map(NewPollChoice::new).collect();
+
+        // Create poll request
+        let request = CreatePollRequest::new();
+        let body = CreatePollBody::new(&user_id, title, duration, &choices[..]);
+
+        // Send request and get response
+        let response: CreatePollResponse = self
+            .helix_client
+            .req_post(request, body, &token)
+            .await?
+            .data;
+
+        Ok(response)
+    }
+
+    pub async fn create_prediction(
+        &self,
+        title: String,
+        outcomes: Vec<String>,
+        prediction_window: i64,
+    ) -> anyhow::Result<CreatePredictionResponse> {
+        // Obtain twitch access token
+        let token = self.get_user_token().await.context("not authenticated")?;
+
+        // Get broadcaster user ID
+        let user_id = token.user_id.clone();
+
+        let outcomes: Vec<NewPredictionOutcome> =
+            outcomes.iter().map(NewPredictionOutcome::new).collect();
+
+        // Create prediction request
+        let request = CreatePredictionRequest::new();
+        let body = CreatePredictionBody::new(&user_id, title, &outcomes[..], prediction_window);
+
+        // Send request and get response
+        let response: CreatePredictionResponse = self
+            .helix_client
+            .req_post(request, body, &token)
+            .await?
+            .data;
+
+        Ok(response)
+    }
+
+    pub async fn update_redemption_status(
+        &self,
+        reward_id: &str,
+        redemption_id: &RedemptionId,
+        status: CustomRewardRedemptionStatus,
+    ) -> anyhow::Result<()> {
+        // Obtain twitch access token
+        let token = self.get_user_token().await.context("not authenticated")?;
+
+        // Get broadcaster user ID
+        let user_id = token.user_id.clone();
+
+        // Create update status request
+        let request = UpdateRedemptionStatusRequest::new(&user_id, reward_id, redemption_id);
+        let body = UpdateRedemptionStatusBody::status(status);
+
+        self.helix_client.req_patch(request, body, &token).await?;
+
+        Ok(())
+    }
+
+    /// Create a new custom channel point reward, the reward will be
+    /// owned by our client ID allowing it to be managed by the app
+    pub async fn create_reward(&self, create: CreateReward) -> anyhow::Result<CustomReward> {
+        // Obtain twitch access token
+        let token = self.get_user_token().await.context("not authenticated")?;
+
+        // Get broadcaster user ID
+        let user_id = token.user_id.clone();
+
+        let mut body = CreateCustomRewardBody::new(create.title, create.cost);
+        body.prompt = create.prompt.map(Into::into);
+        body.is_enabled = create.is_enabled;
+        body.is_user_input_required = create.is_user_input_required;
+
+        if let Some(global_cooldown) = create.global_cooldown_seconds {
+            body.is_global_cooldown_enabled = Some(global_cooldown > 0);
+            body.global_cooldown_seconds = Some(global_cooldown);
+        }
+
+        if let Some(max_per_stream) = create.max_per_stream {
+            body.is_max_per_stream_enabled = Some(max_per_stream > 0);
+            body.max_per_stream = Some(max_per_stream);
+        }
+
+        if let Some(max_per_user_per_stream) = create.max_per_user_per_stream {
+            body.is_max_per_user_per_stream_enabled = Some(max_per_user_per_stream > 0);
+            body.max_per_user_per_stream = Some(max_per_user_per_stream);
+        }
+
+        // Create reward request
+        let request = CreateCustomRewardRequest::broadcaster_id(&user_id);
+
+        // Send request and get response
+        let reward: CustomReward = self
+            .helix_client
+            .req_post(request, body, &token)
+            .await?
+            .data;
+
+        self.reload_rewards_list().await;
+
+        Ok(reward)
+    }
+
+    /// Update an existing custom channel point reward, only rewards
+    /// created by our client ID can be updated
+    pub async fn update_reward(
+        &self,
+        reward_id: &str,
+        update: UpdateReward,
+    ) -> anyhow::Result<CustomReward> {
+        // Obtain twitch access token
+        let token = self.get_user_token().await.context("not authenticated")?;
+
+        // Get broadcaster user ID
+        let user_id = token.user_id.clone();
+
+        // Pause state was changed manually, cooldowns must not undo it
+        if update.is_paused.is_some() {
+            self.cooldown_paused_rewards.write().await.remove(reward_id);
+        }
+
+        let mut body = UpdateCustomRewardBody::default();
+        body.title = update.title.map(Into::into);
+        body.prompt = update.prompt.map(Into::into);
+        body.cost = update.cost;
+        body.is_enabled = update.is_enabled;
+        body.is_paused = update.is_paused;
+        body.is_user_input_required = update.is_user_input_required;
+
+        if let Some(global_cooldown) = update.global_cooldown_seconds {
+            body.is_global_cooldown_enabled = Some(global_cooldown > 0);
+            body.global_cooldown_seconds = Some(global_cooldown);
+        }
+
+        if let Some(max_per_stream) = update.max_per_stream {
+            body.is_max_per_stream_enabled = Some(max_per_stream > 0);
+            body.max_per_stream = Some(max_per_stream);
+        }
+
+        if let Some(max_per_user_per_stream) = update.max_per_user_per_stream {
+            body.is_max_per_user_per_stream_enabled = Some(max_per_user_per_stream > 0);
+            body.max_per_user_per_stream = Some(max_per_user_per_stream);
+        }
+
+        // Create update reward request
+        let request = UpdateCustomRewardRequest::new(&user_id, reward_id);
+
+        // Send request and get response
+        let reward: CustomReward = self
+            .helix_client
+            .req_patch(request, body, &token)
+            .await?
+            .data;
+
+        self.reload_rewards_list().await;
+
+        Ok(reward)
+    }
+
+    /// Pause or unpause a custom channel point reward
+    pub async fn set_reward_paused(
+        &self,
+        reward_id: &str,
+        paused: bool,
+    ) -> anyhow::Result<CustomReward> {
+        self.update_reward(
+            reward_id,
+            UpdateReward {
+                is_paused: Some(paused),
+                ..Default::default()
+            },
+        )
+        .await
+    }
+
+    /// Pause a reward while an event is on cooldown, returns whether the
+    /// reward was paused. Rewards that are already paused or were not
+    /// created by our client ID are left as is
+    pub async fn pause_reward_for_cooldown(&self, reward_id: &str) -> anyhow::Result<bool> {
+        self.apply_reward_cooldown_pause(reward_id, false).await
+    }
+
+    /// Restores the pause for a reward that is still on cooldown from before
+    /// the app was restarted, a reward that is already paused is assumed to
+    /// have been paused by that cooldown
+    pub async fn restore_reward_cooldown_pause(&self, reward_id: &str) -> anyhow::Result<bool> {
+        self.apply_reward_cooldown_pause(reward_id, true).await
+    }
+
+    async fn apply_reward_cooldown_pause(
+        &self,
+        reward_id: &str,
+        claim_paused: bool,
+    ) -> anyhow::Result<bool> {
+        let rewards = self.request_rewards_list(true).await?;
+        let reward = match rewards
+            .iter()
+            .find(|reward| reward.id.as_str() == reward_id)
+        {
+            Some(value) => value,
+            None => return Ok(false),
+        };
+
+        if reward.is_paused {
+            if !claim_paused {
+                return Ok(false);
+            }
+        } else {
+            self.set_reward_paused(reward_id, true).await?;
+        }
+
+        self.cooldown_paused_rewards
+            .write()
+            .await
+            .insert(reward_id.to_string());
+
+        Ok(true)
+    }
+
+    /// Unpause a reward paused by [TwitchManager::pause_reward_for_cooldown],
+    /// rewards that had their pause state changed since are left as is
+    pub async fn resume_reward_after_cooldown(&self, reward_id: &str) -> anyhow::Result<()> {
+        if !self.cooldown_paused_rewards.write().await.remove(reward_id) {
+            return Ok(());
+        }
+
+        let rewards = self.request_rewards_list(true).await?;
+        let is_paused = rewards
+            .iter()
+            .any(|reward| reward.id.as_str() == reward_id && reward.is_paused);
+
+        if is_paused {
+            self.set_reward_paused(reward_id, false).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Checks if a reward was created by our client ID, only these
+    /// rewards can be updated by the app
+    pub async fn is_reward_manageable(&self, reward_id: &str) -> anyhow::Result<bool> {
+        let rewards = self.request_rewards_list(true).await?;
+        Ok(rewards.iter().any(|reward| reward.id.as_str() == reward_id))
+    }
+
+    /// Delete a custom channel point reward, only rewards created by
+    /// our client ID can be deleted
+    pub async fn delete_reward(&self, reward_id: &str) -> anyhow::Result<()> {
+        // Obtain twitch access token
+        let token = self.get_user_token().await.context("not authenticated")?;
+
+        // Get broadcaster user ID
+        let user_id = token.user_id.clone();
+
+        // Create delete reward request
+        let request = DeleteCustomRewardRequest::new(&user_id, reward_id);
+
+        self.helix_client.req_delete(request, &token).await?;
+
+        self.reload_rewards_list().await;
+
+        Ok(())
+    }
+
+    /// Reloads the rewards list after a reward has been changed
+    async fn reload_rewards_list(&self) {
+        if let Err(err) = self.load_rewards_list().await {
+            error!("failed to reload rewards: {:?}", err);
+        }
+    }
+
     pub async fn get_user_token(&self) -> Option<UserToken> {
         let lock = &*self.state.read().await;
         match lock {
@@ -292,6 +843,8 @@ impl TwitchManager {
                 moderators: None,
                 vips: None,
                 rewards: None,
+                stream: None,
+                subscriptions: HashMap::new(),
             });
         }
 
@@ -316,6 +869,16 @@ impl TwitchManager {
         if let Err(err) = mods_result {
             error!("failed to load mods: {:?}", err);
         }
+
+        // Load the initial live state
+        match self.get_current_stream_id().await {
+            Ok(stream_id) => {
+                _ = self.tx.send(TwitchEvent::LiveStateLoaded(stream_id));
+            }
+            Err(err) => {
+                error!("failed to load current stream: {:?}", err);
+            }
+        }
     }
 
     pub async fn reset(&self) {
@@ -328,6 +891,43 @@ impl TwitchManager {
         _ = self.app_handle.emit("logout", ());
     }
 
+    pub async fn attempt_bot_auth_existing_token(
+        &self,
+        access_token: AccessToken,
+    ) -> anyhow::Result<()> {
+        // Create user token (Validates it with the twitch backend)
+        let user_token =
+            UserToken::from_existing(&self.helix_client, access_token, None, None).await?;
+
+        self.set_bot_authenticated(user_token).await;
+
+        Ok(())
+    }
+
+    /// Get the token for the bot account, [None] when no bot account is connected
+    pub async fn get_bot_token(&self) -> Option<UserToken> {
+        self.bot_token.read().await.clone()
+    }
+
+    /// Sets the bot account used for sending chat messages, events are
+    /// still received using the broadcaster account
+    pub async fn set_bot_authenticated(&self, token: UserToken) {
+        *self.bot_token.write().await = Some(token);
+        *self.chat_sender_elevated.write().await = None;
+
+        // Tell the app the bot is authenticated
+        _ = self.app_handle.emit("bot_authenticated", ());
+    }
+
+    /// Removes the bot account, chat messages are sent by the broadcaster
+    pub async fn reset_bot(&self) {
+        *self.bot_token.write().await = None;
+        *self.chat_sender_elevated.write().await = None;
+
+        // Tell the app the bot was logged out
+        _ = self.app_handle.emit("bot_logout", ());
+    }
+
     pub async fn get_moderator_list(&self) -> anyhow::Result<Arc<[Moderator]>> {
         // First attempt to read existing list
         {
@@ -398,6 +998,110 @@ impl TwitchManager {
         }
     }
 
+    /// Get the ID of the current stream, [None] when the broadcaster
+    /// is not live
+    pub async fn get_current_stream_id(&self) -> anyhow::Result<Option<StreamId>> {
+        // First attempt to read existing stream details
+        {
+            let state = &*self.state.read().await;
+            match state {
+                TwitchManagerState::Initial => return Err(anyhow!("not authenticated")),
+                TwitchManagerState::Authenticated(state) => {
+                    if let Some(stream) = state.stream.as_ref() {
+                        if stream.loaded_at.elapsed() < STREAM_CACHE_DURATION {
+                            return Ok(stream.stream_id.clone());
+                        }
+                    }
+                }
+            }
+        }
+
+        let stream_id = self.request_current_stream().await?.map(|stream| stream.id);
+
+        // Write new stream details
+        let state = &mut *self.state.write().await;
+        match state {
+            TwitchManagerState::Initial => Err(anyhow!("not authenticated")),
+            TwitchManagerState::Authenticated(state) => {
+                state.stream = Some(CachedStream {
+                    stream_id: stream_id.clone(),
+                    loaded_at: Instant::now(),
+                });
+                Ok(stream_id)
+            }
+        }
+    }
+
+    /// Get the tier the user is subscribed to the broadcaster at,
+    /// [None] when the user is not subscribed
+    pub async fn get_subscription_tier(
+        &self,
+        user_id: UserId,
+    ) -> anyhow::Result<Option<SubscriptionTier>> {
+        // First attempt to read existing subscription details
+        {
+            let state = &*self.state.read().await;
+            match state {
+                TwitchManagerState::Initial => return Err(anyhow!("not authenticated")),
+                TwitchManagerState::Authenticated(state) => {
+                    if let Some(subscription) = state.subscriptions.get(&user_id) {
+                        if subscription.loaded_at.elapsed() < SUBSCRIPTION_CACHE_DURATION {
+                            return Ok(subscription.tier.clone());
+                        }
+                    }
+                }
+            }
+        }
+
+        let tier = self
+            .request_subscription(user_id.clone())
+            .await?
+            .map(|subscription| subscription.tier);
+
+        // Write new subscription details
+        let state = &mut *self.state.write().await;
+        match state {
+            TwitchManagerState::Initial => Err(anyhow!("not authenticated")),
+            TwitchManagerState::Authenticated(state) => {
+                // Remove expired entries
+                state.subscriptions.retain(|_, subscription| {
+                    subscription.loaded_at.elapsed() < SUBSCRIPTION_CACHE_DURATION
+                });
+
+                state.subscriptions.insert(
+                    user_id,
+                    CachedSubscription {
+                        tier: tier.clone(),
+                        loaded_at: Instant::now(),
+                    },
+                );
+
+                Ok(tier)
+            }
+        }
+    }
+
+    /// Clears the cached subscription details for a user, used
+    /// when the user subscription is known to have changed
+    pub async fn clear_subscription(&self, user_id: &UserId) {
+        let state = &mut *self.state.write().await;
+        if let TwitchManagerState::Authenticated(state) = state {
+            state.subscriptions.remove(user_id);
+        }
+    }
+
+    /// Set the ID of the current stream, used to update the stream
+    /// details when the stream goes online or offline
+    pub async fn set_current_stream_id(&self, stream_id: Option<StreamId>) {
+        let state = &mut *self.state.write().await;
+        if let TwitchManagerState::Authenticated(state) = state {
+            state.stream = Some(CachedStream {
+                stream_id,
+                loaded_at: Instant::now(),
+            });
+        }
+    }
+
     pub async fn load_moderator_list(&self) -> anyhow::Result<()> {
         let moderators = self.request_moderator_list().await?;
         let moderators: Arc<[Moderator]> = moderators.into();
@@ -429,7 +1133,7 @@ impl TwitchManager {
     }
 
     pub async fn load_rewards_list(&self) -> anyhow::Result<()> {
-        let rewards = self.request_rewards_list().await?;
+        let rewards = self.request_rewards_list(false).await?;
         let rewards: Arc<[CustomReward]> = rewards.into();
 
         // Write new list
@@ -469,18 +1173,86 @@ impl TwitchManager {
         Ok(moderators)
     }
 
-    async fn request_rewards_list(&self) -> anyhow::Result<Vec<CustomReward>> {
+    async fn request_subscription(
+        &self,
+        user_id: UserId,
+    ) -> anyhow::Result<Option<BroadcasterSubscription>> {
+        let user_token = self.get_user_token().await.context("not authenticated")?;
+        let broadcaster_id = user_token.user_id.clone();
+
+        let request = GetBroadcasterSubscriptionsRequest::broadcaster_id(broadcaster_id)
+            .user_id(&[user_id][..]);
+        let mut subscriptions: Vec<BroadcasterSubscription> =
+            self.helix_client.req_get(request, &user_token).await?.data;
+
+        Ok(subscriptions.pop())
+    }
+
+    async fn request_current_stream(&self) -> anyhow::Result<Option<Stream>> {
+        let user_token = self.get_user_token().await.context("not authenticated")?;
+        let user_id = user_token.user_id.clone();
+
+        let request = GetStreamsRequest::user_ids(&[user_id][..]);
+        let mut streams: Vec<Stream> = self.helix_client.req_get(request, &user_token).await?.data;
+
+        Ok(streams.pop())
+    }
+
+    async fn request_rewards_list(
+        &self,
+        only_manageable: bool,
+    ) -> anyhow::Result<Vec<CustomReward>> {
         let user_token = self.get_user_token().await.context("not authenticated")?;
         let user_id = user_token.user_id.clone();
         let rewards = self
             .helix_client
-            .get_all_custom_rewards(user_id, false, &user_token)
+            .get_all_custom_rewards(user_id, only_manageable, &user_token)
             .await?;
 
         Ok(rewards)
     }
 }
 
+/// Details for creating a new custom channel point reward
+#[derive(Debug, Deserialize)]
+pub struct CreateReward {
+    pub title: String,
+    pub cost: usize,
+    #[serde(default)]
+    pub prompt: Option<String>,
+    #[serde(default)]
+    pub is_enabled: Option<bool>,
+    #[serde(default)]
+    pub is_user_input_required: Option<bool>,
+    /// Global cooldown in seconds, zero disables the cooldown
+    #[serde(default)]
+    pub global_cooldown_seconds: Option<usize>,
+    /// Maximum redemptions per stream, zero disables the limit
+    #[serde(default)]
+    pub max_per_stream: Option<usize>,
+    /// Maximum redemptions per user per stream, zero disables the limit
+    #[serde(default)]
+    pub max_per_user_per_stream: Option<usize>,
+}
+
+/// Changes to make to an existing custom channel point reward
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct UpdateReward {
+    pub title: Option<String>,
+    pub cost: Option<usize>,
+    pub prompt: Option<String>,
+    pub is_enabled: Option<bool>,
+    pub is_paused: Option<bool>,
+    pub is_user_input_required: Option<bool>,
+    /// Global cooldown in seconds, zero disables the cooldown
+    pub global_cooldown_seconds: Option<usize>,
+    /// Maximum redemptions per stream, zero disables the limit
+    pub max_per_stream: Option<usize>,
+    /// Maximum redemptions per user per stream, zero disables the limit
+    pub max_per_user_per_stream: Option<usize>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[allow(unused)]
 pub struct TwitchEventUser {
@@ -574,6 +1346,8 @@ pub struct TwitchEventChatMsg {
     pub user_display_name: DisplayName,
     pub message: eventsub::channel::chat::Message,
     pub cheer: Option<Cheer>,
+    /// Whether twitch marked this as the users first message in the channel
+    pub first_message: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -609,6 +1383,46 @@ pub struct TwitchEventShoutoutReceive {
     pub viewer_count: i64,
 }
 
+#[derive(Debug, Clone)]
+#[allow(unused)]
+pub struct TwitchEventHypeTrain {
+    /// Current level of the hype train
+    pub level: i64,
+    /// Total points contributed to the hype train
+    pub total: i64,
+    /// Top contributors to the hype train
+    pub top_contributions: Vec<Contribution>,
+}
+
+#[derive(Debug, Clone)]
+#[allow(unused)]
+pub struct TwitchEventPoll {
+    /// Title of the poll
+    pub title: String,
+    /// Choices available in the poll
+    pub choices: Vec<Choice>,
+}
+
+#[derive(Debug, Clone)]
+#[allow(unused)]
+pub struct TwitchEventPrediction {
+    /// Title of the prediction
+    pub title: String,
+    /// Outcomes available in the prediction
+    pub outcomes: Vec<PredictionOutcome>,
+    /// ID of the winning outcome (Only present when the prediction has ended)
+    pub winning_outcome_id: Option<String>,
+    /// Whether the prediction ended by being canceled
+    pub canceled: bool,
+}
+
+#[derive(Debug, Clone)]
+#[allow(unused)]
+pub struct TwitchEventStreamOnline {
+    /// ID of the stream that went online
+    pub stream_id: StreamId,
+}
+
 #[derive(Debug, Clone)]
 #[allow(unused)]
 pub enum TwitchEvent {
@@ -622,10 +1436,22 @@ pub enum TwitchEvent {
     Raid(TwitchEventRaid),
     AdBreakBegin(TwitchEventAdBreakBegin),
     ShoutoutReceive(TwitchEventShoutoutReceive),
+    HypeTrainBegin(TwitchEventHypeTrain),
+    HypeTrainProgress(TwitchEventHypeTrain),
+    HypeTrainLevelUp(TwitchEventHypeTrain),
+    HypeTrainEnd(TwitchEventHypeTrain),
+    PollBegin(TwitchEventPoll),
+    PollEnd(TwitchEventPoll),
+    PredictionBegin(TwitchEventPrediction),
+    PredictionLock(TwitchEventPrediction),
+    PredictionEnd(TwitchEventPrediction),
+    StreamOnline(TwitchEventStreamOnline),
+    StreamOffline,
 
     ModeratorsChanged,
     VipsChanged,
     RewardsChanged,
+    LiveStateLoaded(Option<StreamId>),
 
     Reset,
 }
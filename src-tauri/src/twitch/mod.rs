@@ -1,2 +1,4 @@
+pub mod chat;
+pub mod chat_queue;
 pub mod manager;
 pub mod websocket;
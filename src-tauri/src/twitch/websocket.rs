@@ -1,7 +1,7 @@
 use super::manager::{
     TwitchEvent, TwitchEventAdBreakBegin, TwitchEventChatMsg, TwitchEventCheerBits,
-    TwitchEventFollow, TwitchEventGiftSub, TwitchEventRaid, TwitchEventReSub, TwitchEventRedeem,
-    TwitchEventShoutoutReceive, TwitchEventSub,
+    TwitchEventFollow, TwitchEventGiftSub, TwitchEventHypeTrain, TwitchEventRaid, TwitchEventReSub,
+    TwitchEventRedeem, TwitchEventShoutoutReceive, TwitchEventSub,
 };
 use anyhow::Context;
 use axum::async_trait;
@@ -23,11 +23,15 @@ use tungstenite::{
 use twitch_api::{
     eventsub::{
         self,
-        channel::ChannelRaidV1,
+        channel::{
+            chat::message::MessageType, poll::end::PollEndStatus,
+            prediction::end::PredictionEndStatus, ChannelRaidV1,
+        },
         event::websocket::{EventsubWebsocketData, SessionData},
         Event, EventSubscription, PayloadParseError, Transport,
     },
     twitch_oauth2::{TwitchToken, UserToken},
+    types::StreamId,
     HelixClient,
 };
 
@@ -66,6 +70,8 @@ pub struct WebsocketClient {
     pub connect_url: String,
     /// Sender for twitch events
     pub tx: broadcast::Sender<TwitchEvent>,
+    /// Level of the currently active hype train, used to detect level ups
+    pub hype_train_level: Option<i64>,
 }
 
 fn websocket_config() -> WebSocketConfig {
@@ -87,6 +93,16 @@ async fn websocket_connect(
         .map(|(socket, _)| socket)
 }
 
+/// Updates the stored hype train `level`, returns whether the progress reached
+/// a new level. When the begin event was missed (i.e after a restart) the level
+/// is seeded from the first progress event so later progress can level up
+fn update_hype_train_level(current: &mut Option<i64>, level: i64) -> bool {
+    match current.replace(level) {
+        Some(previous) => level > previous,
+        None => false,
+    }
+}
+
 fn map_message<E: EventSubscription + Clone>(
     message: eventsub::Message<E>,
 ) -> Result<E::Payload, WebsocketError> {
@@ -110,6 +126,7 @@ impl WebsocketClient {
             client,
             connect_url: twitch_api::TWITCH_EVENTSUB_WEBSOCKET_URL.to_string(),
             tx,
+            hype_train_level: None,
         }
     }
 
@@ -267,6 +284,7 @@ impl WebsocketClient {
                     user_display_name: msg.chatter_user_name,
                     message: msg.message,
                     cheer: msg.cheer,
+                    first_message: matches!(msg.message_type, MessageType::UserIntro),
                 }));
             }
 
@@ -345,6 +363,130 @@ impl WebsocketClient {
                     }))
             }
 
+            // Hype train started
+            Event::ChannelHypeTrainBeginV1(payload) => {
+                let msg = map_message(payload.message)?;
+                self.hype_train_level = Some(msg.level);
+                _ = self
+                    .tx
+                    .send(TwitchEvent::HypeTrainBegin(TwitchEventHypeTrain {
+                        level: msg.level,
+                        total: msg.total,
+                        top_contributions: msg.top_contributions,
+                    }))
+            }
+
+            // Hype train progressed
+            Event::ChannelHypeTrainProgressV1(payload) => {
+                let msg = map_message(payload.message)?;
+                let event = TwitchEventHypeTrain {
+                    level: msg.level,
+                    total: msg.total,
+                    top_contributions: msg.top_contributions,
+                };
+
+                let is_level_up = update_hype_train_level(&mut self.hype_train_level, msg.level);
+
+                _ = self.tx.send(TwitchEvent::HypeTrainProgress(event.clone()));
+
+                if is_level_up {
+                    _ = self.tx.send(TwitchEvent::HypeTrainLevelUp(event));
+                }
+            }
+
+            // Hype train ended
+            Event::ChannelHypeTrainEndV1(payload) => {
+                let msg = map_message(payload.message)?;
+                self.hype_train_level = None;
+                _ = self
+                    .tx
+                    .send(TwitchEvent::HypeTrainEnd(TwitchEventHypeTrain {
+                        level: msg.level,
+                        total: msg.total,
+                        top_contributions: msg.top_contributions,
+                    }))
+            }
+
+            // Poll started
+            Event::ChannelPollBeginV1(payload) => {
+                let msg = map_message(payload.message)?;
+                _ = self.tx.send(TwitchEvent::PollBegin(TwitchEventPoll {
+                    title: msg.title,
+                    choices: msg.choices,
+                }))
+            }
+
+            // Poll ended
+            Event::ChannelPollEndV1(payload) => {
+                let msg = map_message(payload.message)?;
+
+                // Polls are archived after they have already ended, terminated
+                // polls were removed without showing their results
+                if !matches!(msg.status, PollEndStatus::Completed) {
+                    return Ok(());
+                }
+
+                _ = self.tx.send(TwitchEvent::PollEnd(TwitchEventPoll {
+                    title: msg.title,
+                    choices: msg.choices,
+                }))
+            }
+
+            // Prediction started
+            Event::ChannelPredictionBeginV1(payload) => {
+                let msg = map_message(payload.message)?;
+                _ = self
+                    .tx
+                    .send(TwitchEvent::PredictionBegin(TwitchEventPrediction {
+                        title: msg.title,
+                        outcomes: msg.outcomes,
+                        winning_outcome_id: None,
+                        canceled: false,
+                    }))
+            }
+
+            // Prediction locked
+            Event::ChannelPredictionLockV1(payload) => {
+                let msg = map_message(payload.message)?;
+                _ = self
+                    .tx
+                    .send(TwitchEvent::PredictionLock(TwitchEventPrediction {
+                        title: msg.title,
+                        outcomes: msg.outcomes,
+                        winning_outcome_id: None,
+                        canceled: false,
+                    }))
+            }
+
+            // Prediction ended
+            Event::ChannelPredictionEndV1(payload) => {
+                let msg = map_message(payload.message)?;
+                _ = self
+                    .tx
+                    .send(TwitchEvent::PredictionEnd(TwitchEventPrediction {
+                        title: msg.title,
+                        outcomes: msg.outcomes,
+                        winning_outcome_id: msg.winning_outcome_id,
+                        canceled: matches!(msg.status, PredictionEndStatus::Canceled),
+                    }))
+            }
+
+            // Stream went online
+            Event::StreamOnlineV1(payload) => {
+                let msg = map_message(payload.message)?;
+                _ = self
+                    .tx
+                    .send(TwitchEvent::StreamOnline(TwitchEventStreamOnline {
+                        stream_id: StreamId::new(msg.id.to_string()),
+                    }))
+            }
+
+            // Stream went offline
+            Event::StreamOfflineV1(payload) => {
+                let _msg = map_message(payload.message)?;
+                _ = self.tx.send(TwitchEvent::StreamOffline)
+            }
+
             _ => {}
         }
 
@@ -378,13 +520,17 @@ impl WebsocketClient {
     async fn create_subscriptions(&self) -> anyhow::Result<()> {
         use eventsub::channel::{
             ChannelAdBreakBeginV1, ChannelChatMessageV1, ChannelCheerV1, ChannelFollowV2,
+            ChannelHypeTrainBeginV1, ChannelHypeTrainEndV1, ChannelHypeTrainProgressV1,
             ChannelModeratorAddV1, ChannelModeratorRemoveV1, ChannelPointsCustomRewardAddV1,
             ChannelPointsCustomRewardRedemptionAddV1, ChannelPointsCustomRewardRemoveV1,
-            ChannelPointsCustomRewardUpdateV1, ChannelShoutoutReceiveV1, ChannelSubscribeV1,
-            ChannelSubscriptionGiftV1, ChannelSubscriptionMessageV1, ChannelVipAddV1,
-            ChannelVipRemoveV1,
+            ChannelPointsCustomRewardUpdateV1, ChannelPollBeginV1, ChannelPollEndV1,
+            ChannelPredictionBeginV1, ChannelPredictionEndV1, ChannelPredictionLockV1,
+            ChannelShoutoutReceiveV1, ChannelSubscribeV1, ChannelSubscriptionGiftV1,
+            ChannelSubscriptionMessageV1, ChannelVipAddV1, ChannelVipRemoveV1,
         };
 
+        use eventsub::stream::{StreamOfflineV1, StreamOnlineV1};
+
         let session_id = self.session_id.as_deref().context("no active session")?;
 
         let token = &self.token;
@@ -458,6 +604,46 @@ impl WebsocketClient {
                 user_id.clone(),
                 user_id.clone(),
             ))),
+            // Subscribe to hype train started
+            Box::new(EventSub(ChannelHypeTrainBeginV1::broadcaster_user_id(
+                user_id.clone(),
+            ))),
+            // Subscribe to hype train progress
+            Box::new(EventSub(ChannelHypeTrainProgressV1::broadcaster_user_id(
+                user_id.clone(),
+            ))),
+            // Subscribe to hype train ended
+            Box::new(EventSub(ChannelHypeTrainEndV1::broadcaster_user_id(
+                user_id.clone(),
+            ))),
+            // Subscribe to poll started
+            Box::new(EventSub(ChannelPollBeginV1::broadcaster_user_id(
+                user_id.clone(),
+            ))),
+            // Subscribe to poll ended
+            Box::new(EventSub(ChannelPollEndV1::broadcaster_user_id(
+                user_id.clone(),
+            ))),
+            // Subscribe to prediction started
+            Box::new(EventSub(ChannelPredictionBeginV1::broadcaster_user_id(
+                user_id.clone(),
+            ))),
+            // Subscribe to prediction locked
+            Box::new(EventSub(ChannelPredictionLockV1::broadcaster_user_id(
+                user_id.clone(),
+            ))),
+            // Subscribe to prediction ended
+            Box::new(EventSub(ChannelPredictionEndV1::broadcaster_user_id(
+                user_id.clone(),
+            ))),
+            // Subscribe to stream online
+            Box::new(EventSub(StreamOnlineV1::broadcaster_user_id(
+                user_id.clone(),
+            ))),
+            // Subscribe to stream offline
+            Box::new(EventSub(StreamOfflineV1::broadcaster_user_id(
+                user_id.clone(),
+            ))),
         ];
 
         let mut subscriptions = subscriptions.into_iter().peekable();
@@ -503,3 +689,20 @@ impl<T: EventSubscription + Send + 'static> EventSubTrait for EventSub<T> {
         })
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::update_hype_train_level;
+
+    #[test]
+    fn test_hype_train_level_up() {
+        // Progress without a begin event seeds the level
+        let mut level = None;
+        assert!(!update_hype_train_level(&mut level, 2));
+        assert_eq!(level, Some(2));
+
+        assert!(!update_hype_train_level(&mut level, 2));
+        assert!(update_hype_train_level(&mut level, 3));
+        assert_eq!(level, Some(3));
+    }
+}
@@ -0,0 +1,98 @@
+import type {
+  Group,
+  GroupId,
+  CreateGroup,
+  UpdateGroup,
+  UpdateOrdering,
+} from "$shared/dataV2";
+
+import { invoke } from "@tauri-apps/api/core";
+import { createQuery } from "@tanstack/svelte-query";
+
+import { queryClient } from "./utils";
+
+const GROUPS_KEY = ["groups"];
+
+function invalidateGroupsList() {
+  // Invalid the list of groups
+  queryClient.invalidateQueries({ queryKey: GROUPS_KEY });
+}
+
+function createGroupKey(id: GroupId) {
+  return ["group", id] as const;
+}
+
+export function getGroupById(groupId: GroupId) {
+  return invoke<Group | null>("get_group_by_id", { groupId });
+}
+
+export async function createGroup(create: CreateGroup) {
+  const group = await invoke<Group>("create_group", { create });
+
+  const groupKey = createGroupKey(group.id);
+  queryClient.setQueryData(groupKey, group);
+
+  invalidateGroupsList();
+
+  return group;
+}
+
+export async function updateGroup(update: UpdateGroup) {
+  const group = await invoke<Group>("update_group", update);
+
+  const groupKey = createGroupKey(group.id);
+  queryClient.setQueryData(groupKey, group);
+
+  invalidateGroupsList();
+
+  return group;
+}
+
+export async function setGroupEnabled(groupId: GroupId, enabled: boolean) {
+  const group = await invoke<Group>("set_group_enabled", { groupId, enabled });
+
+  const groupKey = createGroupKey(group.id);
+  queryClient.setQueryData(groupKey, group);
+
+  invalidateGroupsList();
+
+  return group;
+}
+
+export async function deleteGroup(groupId: GroupId) {
+  await invoke<void>("delete_group", { groupId });
+
+  const groupKey = createGroupKey(groupId);
+
+  // Cancel any queries for the group and clear the current group data
+  queryClient.cancelQueries({ queryKey: groupKey });
+  queryClient.setQueryData(groupKey, undefined);
+
+  invalidateGroupsList();
+
+  // Events and commands in the group are moved out of the group
+  queryClient.invalidateQueries({ queryKey: ["events"] });
+  queryClient.invalidateQueries({ queryKey: ["commands"] });
+}
+
+export async function updateGroupOrder(update: UpdateOrdering[]) {
+  await invoke("update_group_orderings", { update });
+
+  invalidateGroupsList();
+}
+
+// -----------------------------------------------------
+
+export function createGroupsQuery() {
+  return createQuery({
+    queryKey: GROUPS_KEY,
+    queryFn: () => invoke<Group[]>("get_groups"),
+  });
+}
+
+export function createGroupQuery(id: GroupId) {
+  return createQuery({
+    queryKey: createGroupKey(id),
+    queryFn: () => getGroupById(id),
+  });
+}
@@ -16,6 +16,8 @@ import type {
   SoundsConfig,
   PhysicsConfig,
   RuntimeAppData,
+  ChatConfig,
+  OutcomeQueueConfig,
   ExternalsConfig,
   ThrowablesConfig,
   VTubeStudioConfig,
@@ -90,10 +92,24 @@ export function createOverlayURLQuery() {
   });
 }
 
+/**
+ * Pause or resume all outcomes
+ *
+ * @param paused Whether outcomes should be paused
+ * @param flush Whether to drop any queued outcomes and stop any in progress outcomes
+ */
+export function setPaused(paused: boolean, flush: boolean) {
+  return invoke<void>("set_paused", { paused, flush });
+}
+
 export function getTwitchOAuthURI() {
   return invoke<string>("get_twitch_oauth_uri");
 }
 
+export function getTwitchBotOAuthURI() {
+  return invoke<string>("get_twitch_bot_oauth_uri");
+}
+
 /**
  * Creates a derived store that can determine if the
  * current model is calibrated, uses the active model
@@ -149,6 +165,8 @@ type UpdateSettingsMutation = {
   externals_config: Partial<ExternalsConfig>;
   main_config: Partial<MainConfig>;
   physics_config: Partial<PhysicsConfig>;
+  outcome_queue_config: Partial<OutcomeQueueConfig>;
+  chat_config: Partial<ChatConfig>;
 };
 
 export function createUpdateSettingsMutation(
@@ -168,6 +186,8 @@ export function createUpdateSettingsMutation(
         externals_config,
         main_config,
         physics_config,
+        outcome_queue_config,
+        chat_config,
       },
     ) => ({
       ...appData,
@@ -191,6 +211,14 @@ export function createUpdateSettingsMutation(
         ...appData.physics_config,
         ...physics_config,
       },
+      outcome_queue_config: {
+        ...appData.outcome_queue_config,
+        ...outcome_queue_config,
+      },
+      chat_config: {
+        ...appData.chat_config,
+        ...chat_config,
+      },
     }),
   );
 }
@@ -1,7 +1,12 @@
 import { invoke } from "@tauri-apps/api/core";
 import { createQuery, type CreateQueryResult } from "@tanstack/svelte-query";
 
-import type { CustomReward } from "./types";
+import type {
+  TwitchUser,
+  CreateReward,
+  CustomReward,
+  UpdateReward,
+} from "./types";
 
 import { queryClient } from "./utils";
 import { listen } from "@tauri-apps/api/event";
@@ -28,6 +33,49 @@ export async function refreshRedeemsList() {
   queryClient.invalidateQueries({ queryKey: GET_REDEEMS_LIST_KEY });
 }
 
+function invalidateRedeemsList() {
+  queryClient.cancelQueries({ queryKey: GET_REDEEMS_LIST_KEY });
+  queryClient.invalidateQueries({ queryKey: GET_REDEEMS_LIST_KEY });
+}
+
+export async function createReward(create: CreateReward) {
+  const reward = await invoke<CustomReward>("create_reward", { create });
+  invalidateRedeemsList();
+  return reward;
+}
+
+export async function updateReward(rewardId: string, update: UpdateReward) {
+  const reward = await invoke<CustomReward>("update_reward", {
+    rewardId,
+    update,
+  });
+  invalidateRedeemsList();
+  return reward;
+}
+
+export async function setRewardPaused(rewardId: string, paused: boolean) {
+  const reward = await invoke<CustomReward>("set_reward_paused", {
+    rewardId,
+    paused,
+  });
+  invalidateRedeemsList();
+  return reward;
+}
+
+export async function setRewardEnabled(rewardId: string, enabled: boolean) {
+  const reward = await invoke<CustomReward>("set_reward_enabled", {
+    rewardId,
+    enabled,
+  });
+  invalidateRedeemsList();
+  return reward;
+}
+
+export async function deleteReward(rewardId: string) {
+  await invoke<void>("delete_reward", { rewardId });
+  invalidateRedeemsList();
+}
+
 export const IS_AUTHENTICATED_KEY = ["is-authenticated"];
 
 /**
@@ -54,3 +102,34 @@ listen("logout", () => {
   queryClient.cancelQueries({ queryKey: IS_AUTHENTICATED_KEY });
   queryClient.setQueryData(IS_AUTHENTICATED_KEY, false);
 });
+
+export const BOT_USER_KEY = ["bot-user"];
+
+/**
+ * Create a query to fetch the connected bot account
+ */
+export function createBotUserQuery(): CreateQueryResult<
+  TwitchUser | null,
+  Error
+> {
+  return createQuery({
+    queryKey: BOT_USER_KEY,
+    queryFn: () => invoke<TwitchUser | null>("get_twitch_bot_user"),
+  });
+}
+
+export async function logoutBot() {
+  await invoke<void>("logout_bot");
+}
+
+// Handle bot authenticating
+listen("bot_authenticated", () => {
+  queryClient.cancelQueries({ queryKey: BOT_USER_KEY });
+  queryClient.invalidateQueries({ queryKey: BOT_USER_KEY });
+});
+
+// Handle bot logout
+listen("bot_logout", () => {
+  queryClient.cancelQueries({ queryKey: BOT_USER_KEY });
+  queryClient.setQueryData(BOT_USER_KEY, null);
+});
@@ -23,3 +23,25 @@ export type VTubeStudioBroadcast = {
     windowTitle: string;
   };
 };
+
+export type TwitchUser = {
+  id: string;
+  name: string;
+  displayName: string;
+  profileImageUrl: string | null;
+};
+
+export type CreateReward = {
+  title: string;
+  cost: number;
+  prompt?: string | null;
+  is_enabled?: boolean | null;
+  is_user_input_required?: boolean | null;
+  global_cooldown_seconds?: number | null;
+  max_per_stream?: number | null;
+  max_per_user_per_stream?: number | null;
+};
+
+export type UpdateReward = Partial<CreateReward> & {
+  is_paused?: boolean | null;
+};
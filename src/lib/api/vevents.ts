@@ -16,6 +16,8 @@ import type {
 import { invoke } from "@tauri-apps/api/core";
 import { createQuery } from "@tanstack/svelte-query";
 
+import type { CreateReward } from "./types";
+
 import { queryClient } from "./utils";
 
 const EVENTS_KEY = ["events"];
@@ -47,17 +49,46 @@ export async function createEvent(create: CreateEvent, invalidateList = true) {
   return event;
 }
 
+export async function createEventWithReward(
+  create: CreateEvent,
+  reward: CreateReward,
+  invalidateList = true,
+) {
+  const event = await invoke<Event>("create_event_with_reward", {
+    create,
+    reward,
+  });
+
+  const eventKey = createEventKey(event.id);
+  queryClient.setQueryData(eventKey, event);
+
+  if (invalidateList) invalidateEventsList();
+
+  return event;
+}
+
 export async function updateEvent(update: UpdateEvent, invalidateList = true) {
   const event = await invoke<Event>("update_event", update);
 
   const itemKey = createEventKey(event.id);
   queryClient.setQueryData(itemKey, event);
 
+  // Timer changes reschedule the next run
+  queryClient.invalidateQueries({ queryKey: createEventNextRunKey(event.id) });
+
   if (invalidateList) invalidateEventsList();
 
   return event;
 }
 
+function createEventNextRunKey(id: EventId) {
+  return ["event-next-run", id] as const;
+}
+
+export function getEventNextRun(eventId: EventId) {
+  return invoke<string | null>("get_event_next_run", { eventId });
+}
+
 export function testEvent(eventId: EventId, eventData: VEventData) {
   return invoke<Event>("test_event_by_id", { eventId, eventData });
 }
@@ -184,6 +215,15 @@ export function createEventQuery(id: EventId) {
   });
 }
 
+export function createEventNextRunQuery(id: EventId) {
+  return createQuery({
+    queryKey: createEventNextRunKey(id),
+    queryFn: () => getEventNextRun(id),
+    // Timers reschedule after each run
+    refetchInterval: 30 * 1000,
+  });
+}
+
 export function eventLogsQuery(eventId: EventId, query: LogsQuery) {
   return createQuery({
     queryKey: createEventLogsKey(eventId, query),
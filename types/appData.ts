@@ -15,8 +15,35 @@ export type AppData = {
   vtube_studio_config: VTubeStudioConfig;
   externals_config: ExternalsConfig;
   physics_config: PhysicsConfig;
+  outcome_queue_config: OutcomeQueueConfig;
+  chat_config: ChatConfig;
+  paused: boolean;
 };
 
+export type ChatConfig = {
+  continuation_prefix: string;
+  send_as_bot: boolean;
+};
+
+export type OutcomeQueueConfig = {
+  serial: boolean;
+  max_concurrency: number;
+  separate_lanes: boolean;
+  max_length: number;
+  drop_policy: QueueDropPolicy;
+  outcome_spacing: number;
+};
+
+export enum QueueDropPolicy {
+  DropNewest = "DropNewest",
+  DropOldest = "DropOldest",
+}
+
+export const QUEUE_DROP_POLICY_VALUES = [
+  QueueDropPolicy.DropNewest,
+  QueueDropPolicy.DropOldest,
+] as const;
+
 export type PhysicsConfig = {
   enabled: boolean;
   fps: number;
@@ -109,6 +136,9 @@ export type ThrowableImageConfig = {
 export enum MinimumRequiredRole {
   None = "None",
   Follower = "Follower",
+  Subscriber = "Subscriber",
+  SubscriberTier2 = "SubscriberTier2",
+  SubscriberTier3 = "SubscriberTier3",
   Vip = "Vip",
   Mod = "Mod",
   Broadcaster = "Broadcaster",
@@ -117,6 +147,9 @@ export enum MinimumRequiredRole {
 export const MINIMUM_REQUIRED_ROLE_VALUES = [
   MinimumRequiredRole.None,
   MinimumRequiredRole.Follower,
+  MinimumRequiredRole.Subscriber,
+  MinimumRequiredRole.SubscriberTier2,
+  MinimumRequiredRole.SubscriberTier3,
   MinimumRequiredRole.Vip,
   MinimumRequiredRole.Mod,
   MinimumRequiredRole.Broadcaster,
@@ -133,12 +166,56 @@ export enum EventTriggerType {
   Timer = "Timer",
   AdBreakBegin = "AdBreakBegin",
   ShoutoutReceive = "ShoutoutReceive",
+  ChatMessage = "ChatMessage",
+  FirstMessage = "FirstMessage",
+  FirstStreamMessage = "FirstStreamMessage",
+  HypeTrainBegin = "HypeTrainBegin",
+  HypeTrainProgress = "HypeTrainProgress",
+  HypeTrainLevelUp = "HypeTrainLevelUp",
+  HypeTrainEnd = "HypeTrainEnd",
+  PollBegin = "PollBegin",
+  PollEnd = "PollEnd",
+  PredictionBegin = "PredictionBegin",
+  PredictionLock = "PredictionLock",
+  PredictionEnd = "PredictionEnd",
+  StreamOnline = "StreamOnline",
+  StreamOffline = "StreamOffline",
+}
+
+export enum Weekday {
+  Mon = "Mon",
+  Tue = "Tue",
+  Wed = "Wed",
+  Thu = "Thu",
+  Fri = "Fri",
+  Sat = "Sat",
+  Sun = "Sun",
+}
+
+export type TimerWindow = {
+  // Local time the window opens (HH:MM:SS)
+  start: string;
+  // Local time the window closes (HH:MM:SS)
+  end: string;
+  // Days the window is open on, empty for every day
+  days: Weekday[];
+};
+
+export enum ChatMessageMatcherType {
+  Keyword = "Keyword",
+  Regex = "Regex",
 }
 
+export type ChatMessageMatcher =
+  | { type: ChatMessageMatcherType.Keyword; keyword: string }
+  | { type: ChatMessageMatcherType.Regex; pattern: string };
+
 export type EventTrigger =
   | {
       type: EventTriggerType.Redeem;
       reward_id: string;
+      auto_fulfill: boolean;
+      auto_refund: boolean;
     }
   | { type: EventTriggerType.Command; message: string }
   | { type: EventTriggerType.Follow }
@@ -152,6 +229,11 @@ export type EventTrigger =
   | {
       type: EventTriggerType.Timer;
       interval: number;
+      only_while_live: boolean;
+      window: TimerWindow | null;
+      jitter: number;
+      min_chat_messages: number;
+      min_unique_chatters: number;
     }
   | {
       type: EventTriggerType.AdBreakBegin;
@@ -159,7 +241,25 @@ export type EventTrigger =
   | {
       type: EventTriggerType.ShoutoutReceive;
       min_viewers: number;
-    };
+    }
+  | {
+      type: EventTriggerType.ChatMessage;
+      matcher: ChatMessageMatcher;
+      case_sensitive: boolean;
+    }
+  | { type: EventTriggerType.FirstMessage }
+  | { type: EventTriggerType.FirstStreamMessage }
+  | { type: EventTriggerType.HypeTrainBegin }
+  | { type: EventTriggerType.HypeTrainProgress }
+  | { type: EventTriggerType.HypeTrainLevelUp; min_level: number }
+  | { type: EventTriggerType.HypeTrainEnd }
+  | { type: EventTriggerType.PollBegin }
+  | { type: EventTriggerType.PollEnd }
+  | { type: EventTriggerType.PredictionBegin }
+  | { type: EventTriggerType.PredictionLock }
+  | { type: EventTriggerType.PredictionEnd }
+  | { type: EventTriggerType.StreamOnline }
+  | { type: EventTriggerType.StreamOffline };
 
 export enum ThrowableDataType {
   Throw = "Throw",
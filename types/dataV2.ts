@@ -99,9 +99,28 @@ export type Command = {
   outcome: CommandOutcome;
   cooldown: CommandCooldown;
   require_role: MinimumRequiredRole;
+  user_access: UserAccessList;
+  feedback_replies: FeedbackReplies;
+  group_id: GroupId | null;
   order: number;
 };
 
+export type UserAccessList = {
+  allowed: UserAccessEntry[];
+  denied: UserAccessEntry[];
+};
+
+export type UserAccessEntry = {
+  user_id: string;
+  display_name: string;
+};
+
+export type FeedbackReplies = {
+  cooldown: string | null;
+  missing_role: string | null;
+  disabled: string | null;
+};
+
 export type CommandCooldown = {
   enabled: boolean;
   duration: number;
@@ -116,6 +135,9 @@ export type CreateCommand = {
   outcome: CommandOutcome;
   cooldown: CommandCooldown;
   require_role: MinimumRequiredRole;
+  user_access?: UserAccessList;
+  feedback_replies?: FeedbackReplies;
+  group_id?: GroupId | null;
 };
 
 export type UpdateCommand = {
@@ -128,6 +150,38 @@ export type UpdateCommand = {
     outcome: CommandOutcome;
     cooldown: CommandCooldown;
     require_role: MinimumRequiredRole;
+    user_access: UserAccessList;
+    feedback_replies: FeedbackReplies;
+    group_id: GroupId | null;
+    order: number;
+  }>;
+};
+
+export type GroupId = Uuid;
+
+export type Group = {
+  id: GroupId;
+  name: string;
+  enabled: boolean;
+  default_cooldown: number | null;
+  default_require_role: MinimumRequiredRole;
+  order: number;
+};
+
+export type CreateGroup = {
+  name: string;
+  enabled: boolean;
+  default_cooldown: number | null;
+  default_require_role: MinimumRequiredRole;
+};
+
+export type UpdateGroup = {
+  groupId: GroupId;
+  update: Partial<{
+    name: string;
+    enabled: boolean;
+    default_cooldown: number | null;
+    default_require_role: MinimumRequiredRole;
     order: number;
   }>;
 };
@@ -142,6 +196,10 @@ export type VEvent = {
   outcome: EventOutcome;
   cooldown: VEventCooldown;
   require_role: MinimumRequiredRole;
+  conditions: EventCondition[];
+  user_access: UserAccessList;
+  feedback_replies: FeedbackReplies;
+  group_id: GroupId | null;
   outcome_delay: number;
   order: number;
 };
@@ -159,9 +217,41 @@ export type CreateEvent = {
   outcome: EventOutcome;
   cooldown: VEventCooldown;
   require_role: MinimumRequiredRole;
+  conditions?: EventCondition[];
+  user_access?: UserAccessList;
+  feedback_replies?: FeedbackReplies;
+  group_id?: GroupId | null;
   outcome_delay: number;
 };
 
+export enum EventConditionType {
+  InputAmount = "InputAmount",
+  SubscriptionTier = "SubscriptionTier",
+  InputTextContains = "InputTextContains",
+  InputTextMatches = "InputTextMatches",
+}
+
+export type EventCondition =
+  | {
+      type: EventConditionType.InputAmount;
+      min: number | null;
+      max: number | null;
+    }
+  | {
+      type: EventConditionType.SubscriptionTier;
+      tier: SubscriptionTier;
+    }
+  | {
+      type: EventConditionType.InputTextContains;
+      value: string;
+      ignore_case: boolean;
+    }
+  | {
+      type: EventConditionType.InputTextMatches;
+      pattern: string;
+      ignore_case: boolean;
+    };
+
 export type UpdateEvent = {
   eventId: EventId;
   update: Partial<{
@@ -171,6 +261,10 @@ export type UpdateEvent = {
     outcome: EventOutcome;
     cooldown: VEventCooldown;
     require_role: MinimumRequiredRole;
+    conditions: EventCondition[];
+    user_access: UserAccessList;
+    feedback_replies: FeedbackReplies;
+    group_id: GroupId | null;
     outcome_delay: number;
     order: number;
   }>;
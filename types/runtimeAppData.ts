@@ -4,6 +4,16 @@ export type RuntimeAppData = {
   vtube_studio_auth: boolean;
   hotkeys: VTubeStudioHotkey[];
   active_overlay_count: number;
+  is_live: boolean;
+  outcome_queue_depth: number;
+  chat_queue: ChatQueueStatus;
+  paused: boolean;
+};
+
+export type ChatQueueStatus = {
+  length: number;
+  rate_limited: boolean;
+  suppressed: number;
 };
 
 export type VTubeStudioHotkey = {